@@ -7,11 +7,10 @@
 //! This example is based on the `embedded-graphics` example `clock.rs`.
 
 use std::time::Instant;
-use vexide::{
-    display::{RenderMode, TouchState},
-    prelude::*,
+use vexide::prelude::*;
+use vexide_embedded_graphics::{
+    DisplayDriver, DisplayError, FramePacer, RenderMode, TouchState, measure_text,
 };
-use vexide_embedded_graphics::DisplayDriver;
 
 use core::f32::consts::PI;
 use embedded_graphics::{
@@ -139,19 +138,13 @@ fn draw_digital_clock<D>(
 where
     D: DrawTarget<Color = Rgb888>,
 {
-    // Create a styled text object for the time text.
-    let mut text = Text::new(
-        time_str,
-        Point::zero(),
-        MonoTextStyle::new(&FONT_9X15, Rgb888::CSS_BLACK),
-    );
-
-    // Move text to be centered between the 12 o'clock point and the center of the clock face.
-    text.translate_mut(
-        clock_face.center()
-            - text.bounding_box().center()
-            - clock_face.bounding_box().size.y_axis() / 4,
-    );
+    let style = MonoTextStyle::new(&FONT_9X15, Rgb888::CSS_BLACK);
+
+    // Center the text between the 12 o'clock point and the center of the clock face.
+    let position = clock_face.center()
+        - measure_text(time_str, &style).center()
+        - clock_face.bounding_box().size.y_axis() / 4;
+    let text = Text::new(time_str, position, style);
 
     // Add a background around the time digits.
     // Note that there is no bottom-right padding as this is added by the font renderer itself.
@@ -170,13 +163,15 @@ where
 }
 
 #[vexide::main]
-async fn main(peripherals: Peripherals) -> Result<(), core::convert::Infallible> {
-    let mut display = DisplayDriver::new(peripherals.display);
-    display.set_render_mode(RenderMode::DoubleBuffered);
+async fn main(peripherals: Peripherals) -> Result<(), DisplayError> {
+    let mut display =
+        DisplayDriver::with_render_mode(peripherals.display, RenderMode::DoubleBuffered);
 
     let clock_face = create_face(&display);
 
     let start = Instant::now();
+    // Unlike a fixed sleep, the pacer takes the time spent drawing each frame into account.
+    let mut pacer = FramePacer::from_hz(20);
     'running: loop {
         let time = start.elapsed();
 
@@ -217,6 +212,6 @@ async fn main(peripherals: Peripherals) -> Result<(), core::convert::Infallible>
 
         display.render();
 
-        sleep(core::time::Duration::from_millis(50)).await;
+        pacer.wait().await;
     }
 }
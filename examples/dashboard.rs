@@ -0,0 +1,55 @@
+//! # Example: Dashboard
+//!
+//! This example shows how to build a retained dashboard of widgets that is only redrawn where
+//! something changed. A progress bar and a gauge follow a slowly changing value, and a text field
+//! shows how long the program has been running. Touch the screen to exit.
+
+use std::time::Instant;
+use vexide::prelude::*;
+use vexide_embedded_graphics::{
+    Dashboard, DisplayDriver, DisplayError, FramePacer, TouchState, Widget, WidgetStyle,
+};
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+
+#[vexide::main]
+async fn main(peripherals: Peripherals) -> Result<(), DisplayError> {
+    let mut display = DisplayDriver::with_clear(peripherals.display, Rgb888::BLACK);
+    let style = WidgetStyle::new(Rgb888::CSS_LIME_GREEN, Rgb888::BLACK);
+
+    let mut dashboard = Dashboard::new();
+    let uptime = dashboard.add(Widget::text_field(
+        Rectangle::new(Point::new(20, 20), Size::new(200, 20)),
+        style,
+    ));
+    let bar = dashboard.add(Widget::progress_bar(
+        Rectangle::new(Point::new(20, 60), Size::new(200, 20)),
+        style,
+    ));
+    let gauge = dashboard.add(Widget::gauge(
+        Rectangle::new(Point::new(260, 40), Size::new(160, 160)),
+        style,
+    ));
+
+    let start = Instant::now();
+    let mut pacer = FramePacer::native();
+    loop {
+        let elapsed = start.elapsed();
+        let value = (elapsed.as_secs_f32() * 0.5).sin() * 0.5 + 0.5;
+
+        // Updating a widget only marks it dirty if its value actually changed.
+        dashboard
+            .widget_mut(uptime)
+            .set_text(&format!("Uptime: {}s", elapsed.as_secs()));
+        dashboard.widget_mut(bar).set_value(value);
+        dashboard.widget_mut(gauge).set_value(value);
+
+        dashboard.draw(&mut display)?;
+
+        if matches!(display.touch_status().state, TouchState::Pressed) {
+            break Ok(());
+        }
+
+        pacer.wait().await;
+    }
+}
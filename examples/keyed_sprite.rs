@@ -0,0 +1,62 @@
+//! # Example: Keyed Sprite
+//!
+//! This example shows how to draw a sprite with transparent parts over a background. The sprite
+//! is generated when the program starts: an orange ball on a magenta square, with magenta as the
+//! color key. Drawing it with `draw_image_keyed` leaves the checkerboard showing around the ball.
+//! Touch the screen to exit.
+
+use vexide::prelude::*;
+use vexide_embedded_graphics::{DisplayDriver, DisplayError, FramePacer, TouchState};
+
+use embedded_graphics::{image::ImageRaw, pixelcolor::Rgb888, prelude::*};
+
+/// The width and height of the sprite.
+const SIZE: u32 = 48;
+
+/// The color that stands in for transparency in the sprite.
+const KEY: Rgb888 = Rgb888::MAGENTA;
+
+/// Builds the sprite's pixel data in the big endian RGB order that `ImageRaw` reads.
+fn build_sprite() -> Vec<u8> {
+    let center = SIZE as i32 / 2;
+    let radius = center - 2;
+
+    (0..SIZE as i32 * SIZE as i32)
+        .flat_map(|index| {
+            let (x, y) = (index % SIZE as i32 - center, index / SIZE as i32 - center);
+            let color = if x * x + y * y <= radius * radius {
+                Rgb888::CSS_ORANGE
+            } else {
+                KEY
+            };
+            [color.r(), color.g(), color.b()]
+        })
+        .collect()
+}
+
+#[vexide::main]
+async fn main(peripherals: Peripherals) -> Result<(), DisplayError> {
+    let mut display = DisplayDriver::new(peripherals.display);
+    display.fill_checkerboard(
+        &display.bounding_box(),
+        Rgb888::CSS_DARK_SLATE_GRAY,
+        Rgb888::CSS_SLATE_GRAY,
+        24,
+    );
+
+    let data = build_sprite();
+    let sprite = ImageRaw::<Rgb888>::new(&data, SIZE);
+    for i in 0..5 {
+        let top_left = Point::new(40 + i * 80, 20 + i * 30);
+        display.draw_image_keyed(top_left, &sprite, KEY)?;
+    }
+
+    let mut pacer = FramePacer::native();
+    loop {
+        if matches!(display.touch_status().state, TouchState::Pressed) {
+            break Ok(());
+        }
+
+        pacer.wait().await;
+    }
+}
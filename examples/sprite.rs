@@ -0,0 +1,60 @@
+//! # Example: Sprite Animation
+//!
+//! This example shows how to animate a sprite from a sprite sheet. The sheet is generated when the
+//! program starts: four frames of a bar spinning around its center. Each frame only copies the
+//! rows of one cell to the display. Touch the screen to exit.
+
+use vexide::prelude::*;
+use vexide_embedded_graphics::{DisplayDriver, DisplayError, FramePacer, SpriteSheet, TouchState};
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+/// The size of each frame of the animation.
+const CELL: u32 = 32;
+
+/// The number of frames in the animation.
+const FRAMES: u32 = 4;
+
+/// Builds a sheet of `FRAMES` cells side by side, each showing a bar turned another 45 degrees.
+fn build_sheet() -> Vec<Rgb888> {
+    let width = CELL * FRAMES;
+    let center = CELL as i32 / 2;
+    let mut pixels = vec![Rgb888::BLACK; (width * CELL) as usize];
+
+    for frame in 0..FRAMES as i32 {
+        // Each frame steps the bar's direction around a quarter circle of (dx, dy) offsets.
+        let (dx, dy) = [(1, 0), (1, 1), (0, 1), (-1, 1)][frame as usize];
+        for t in -12..=12 {
+            for thickness in -1..=1 {
+                let x = center + t * dx + thickness * dy;
+                let y = center + t * dy - thickness * dx;
+                let index = y * width as i32 + frame * CELL as i32 + x;
+                pixels[index as usize] = Rgb888::CSS_ORANGE;
+            }
+        }
+    }
+
+    pixels
+}
+
+#[vexide::main]
+async fn main(peripherals: Peripherals) -> Result<(), DisplayError> {
+    let mut display = DisplayDriver::with_clear(peripherals.display, Rgb888::BLACK);
+
+    let pixels = build_sheet();
+    let sheet = SpriteSheet::new(&pixels, Size::new(CELL, CELL), FRAMES);
+    let position = display.bounding_box().center() - Point::new(CELL as i32, CELL as i32) / 2;
+
+    let mut pacer = FramePacer::from_hz(8);
+    for frame in (0..sheet.len()).cycle() {
+        sheet.draw_cell(&mut display, frame, position);
+
+        if matches!(display.touch_status().state, TouchState::Pressed) {
+            break;
+        }
+
+        pacer.wait().await;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,345 @@
+//! Alpha-blended drawing.
+
+use core::convert::Infallible;
+
+use embedded_graphics_core::{
+    pixelcolor::{
+        Rgb888,
+        raw::{RawData, RawU24, RawU32},
+    },
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use crate::{
+    DisplayDriver, PanelBackend, ToDisplayStorage, color_math, frame::safe_intersection, skip,
+};
+
+/// A 32-bit color with 8 bits each for red, green, blue and alpha.
+///
+/// An alpha of `255` is fully opaque and `0` is fully transparent. Colors can be drawn with
+/// transparency through the target returned by [`DisplayDriver::blended`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgba8888 {
+    r: u8,
+    g: u8,
+    b: u8,
+    a: u8,
+}
+
+impl Rgba8888 {
+    /// Creates a new color from its components.
+    #[must_use]
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Creates a new color from an [`Rgb888`] color and an alpha value.
+    #[must_use]
+    pub fn with_alpha(color: Rgb888, a: u8) -> Self {
+        Self::new(color.r(), color.g(), color.b(), a)
+    }
+
+    /// Returns the red component.
+    #[must_use]
+    pub const fn r(self) -> u8 {
+        self.r
+    }
+
+    /// Returns the green component.
+    #[must_use]
+    pub const fn g(self) -> u8 {
+        self.g
+    }
+
+    /// Returns the blue component.
+    #[must_use]
+    pub const fn b(self) -> u8 {
+        self.b
+    }
+
+    /// Returns the alpha component.
+    #[must_use]
+    pub const fn a(self) -> u8 {
+        self.a
+    }
+
+    /// Returns the color without its alpha component.
+    #[must_use]
+    pub const fn rgb(self) -> Rgb888 {
+        Rgb888::new(self.r, self.g, self.b)
+    }
+
+    /// Blends this color over `background`, which is in the display's `0x00RRGGBB` format.
+    pub(crate) fn blend_over(self, background: u32) -> u32 {
+        match self.a {
+            255 => self.rgb().into_storage(),
+            0 => background,
+            a => {
+                color_math::mix(Rgb888::from(RawU24::new(background)), self.rgb(), a).into_storage()
+            }
+        }
+    }
+}
+
+impl PixelColor for Rgba8888 {
+    type Raw = RawU32;
+}
+
+impl From<RawU32> for Rgba8888 {
+    fn from(raw: RawU32) -> Self {
+        let [r, g, b, a] = raw.into_inner().to_be_bytes();
+        Self::new(r, g, b, a)
+    }
+}
+
+impl From<Rgba8888> for RawU32 {
+    fn from(color: Rgba8888) -> Self {
+        RawU32::new(u32::from_be_bytes([color.r, color.g, color.b, color.a]))
+    }
+}
+
+impl From<Rgb888> for Rgba8888 {
+    fn from(color: Rgb888) -> Self {
+        Self::with_alpha(color, 255)
+    }
+}
+
+/// A draw target that alpha-blends [`Rgba8888`] colors over the current display contents.
+///
+/// The display has no way of reading pixels back, so blending is done against the driver's shadow
+/// buffer. This makes every partially transparent pixel cost a read and a blend on top of the
+/// usual write, and means blending is only correct for content drawn through the driver after the
+/// shadow buffer was enabled. Fully opaque pixels skip blending entirely, and fully transparent
+/// pixels are not drawn at all.
+///
+/// Created with [`DisplayDriver::blended`].
+pub struct Blended<'a, C, B: PanelBackend> {
+    driver: &'a mut DisplayDriver<C, B>,
+}
+
+impl<C: ToDisplayStorage, B: PanelBackend> DisplayDriver<C, B> {
+    /// Returns a draw target for drawing [`Rgba8888`] colors with transparency.
+    ///
+    /// This enables the shadow buffer if it isn't already enabled, which is needed to know what
+    /// each pixel is being blended over. See [`Blended`] for the performance implications.
+    pub fn blended(&mut self) -> Blended<'_, C, B> {
+        self.enable_shadow_buffer();
+        Blended { driver: self }
+    }
+
+    /// Draws pixels blended over the current display contents, each with its own alpha from `0`
+    /// (transparent) to `255` (opaque).
+    ///
+    /// This is a shortcut for drawing [`Rgba8888`] pixels through [`blended`](Self::blended),
+    /// for code that has a color and an alpha for each pixel rather than a drawable. Like
+    /// `draw_iter`, pixels outside of the display are ignored.
+    pub fn draw_blended(&mut self, pixels: impl IntoIterator<Item = (Point, Rgb888, u8)>) {
+        let pixels = pixels
+            .into_iter()
+            .map(|(point, color, alpha)| Pixel(point, Rgba8888::with_alpha(color, alpha)));
+        let Ok(()) = self.blended().draw_iter(pixels);
+    }
+}
+
+impl<C, B: PanelBackend> Blended<'_, C, B> {
+    fn background(&self, point: Point) -> u32 {
+        self.driver
+            .shadow_pixel(point)
+            .expect("shadow buffer is enabled while blending")
+    }
+}
+
+impl<C, B: PanelBackend> OriginDimensions for Blended<'_, C, B> {
+    fn size(&self) -> Size {
+        self.driver.size()
+    }
+}
+
+impl<C: ToDisplayStorage, B: PanelBackend> DrawTarget for Blended<'_, C, B> {
+    type Color = Rgba8888;
+
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(pos, color) in pixels.into_iter().filter(|Pixel(_, color)| color.a() != 0) {
+            if let Some(pos) = self.driver.to_screen(pos) {
+                let blended = color.blend_over(self.background(pos));
+                self.driver.write_pixel(pos, blended);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let visible = safe_intersection(area, &self.driver.bounding_box());
+        if visible.is_zero_sized() {
+            return Ok(());
+        }
+
+        // Colors outside the screen are skipped over like `DisplayDriver::fill_contiguous` does,
+        // which also stops after the last visible row.
+        let width = visible.size.width as usize;
+        let skip_left = visible.top_left.x.abs_diff(area.top_left.x) as usize;
+        let skip_right = area.size.width as usize - skip_left - width;
+        let skip_above = (visible.top_left.y.abs_diff(area.top_left.y) as usize)
+            .saturating_mul(area.size.width as usize);
+        let mut colors = colors.into_iter();
+        if !skip(&mut colors, skip_above) {
+            return Ok(());
+        }
+
+        for y in visible.rows() {
+            if !skip(&mut colors, skip_left) {
+                return Ok(());
+            }
+
+            let row = Rectangle::new(
+                Point::new(visible.top_left.x, y),
+                Size::new(width as u32, 1),
+            );
+            let span = self.driver.screen_rect(&row);
+            let mut read = 0;
+            for (x, color) in row.columns().zip(colors.by_ref()) {
+                read += 1;
+                if let Some(pos) = self.driver.to_screen(Point::new(x, y)) {
+                    let offset = pos - span.top_left;
+                    self.driver.buffer[(offset.x + offset.y) as usize] =
+                        color.blend_over(self.background(pos));
+                }
+            }
+            if read < width {
+                return Ok(());
+            }
+
+            self.driver.write_span(&span);
+            if !skip(&mut colors, skip_right) {
+                return Ok(());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        match color.a() {
+            0 => Ok(()),
+            255 => {
+                self.driver
+                    .fill_solid_storage(area, color.rgb().into_storage());
+                Ok(())
+            }
+            _ => self.fill_contiguous(area, core::iter::repeat(color)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockPanel;
+
+    fn blue_display() -> DisplayDriver<Rgb888, MockPanel> {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.enable_shadow_buffer();
+        display.clear(Rgb888::BLUE).unwrap();
+        display
+    }
+
+    #[test]
+    fn half_alpha_pixels_blend_over_the_background() {
+        let mut display = blue_display();
+
+        let Ok(()) = display.blended().draw_iter([Pixel(
+            Point::new(3, 4),
+            Rgba8888::with_alpha(Rgb888::RED, 128),
+        )]);
+
+        assert_eq!(display.backend().pixel(3, 4), Some(0x80007F));
+        assert_eq!(display.backend().pixel(4, 4), Some(0x0000FF));
+    }
+
+    #[test]
+    fn fill_contiguous_blends_each_pixel() {
+        let mut display = blue_display();
+        let area = Rectangle::new(Point::new(-1, 0), Size::new(3, 1));
+        let colors = [0, 128, 255].map(|a| Rgba8888::with_alpha(Rgb888::RED, a));
+
+        let Ok(()) = display.blended().fill_contiguous(&area, colors);
+
+        assert_eq!(display.backend().pixel(0, 0), Some(0x80007F));
+        assert_eq!(display.backend().pixel(1, 0), Some(0xFF0000));
+        assert_eq!(display.backend().pixel(2, 0), Some(0x0000FF));
+    }
+
+    #[test]
+    fn opaque_and_transparent_fills_skip_blending() {
+        let mut display = blue_display();
+        let area = Rectangle::new(Point::zero(), Size::new(2, 2));
+
+        let Ok(()) = display
+            .blended()
+            .fill_solid(&area, Rgba8888::with_alpha(Rgb888::RED, 0));
+        assert_eq!(display.backend().pixel(0, 0), Some(0x0000FF));
+
+        let Ok(()) = display
+            .blended()
+            .fill_solid(&area, Rgba8888::with_alpha(Rgb888::RED, 255));
+        assert_eq!(display.backend().pixel(1, 1), Some(0xFF0000));
+    }
+
+    #[test]
+    fn draw_blended_takes_an_alpha_per_pixel() {
+        let mut display = blue_display();
+
+        display.draw_blended([
+            (Point::new(0, 0), Rgb888::RED, 128),
+            (Point::new(-1, 0), Rgb888::RED, 128),
+        ]);
+
+        assert_eq!(display.backend().pixel(0, 0), Some(0x80007F));
+    }
+
+    #[test]
+    fn fill_contiguous_over_a_huge_area_is_clipped() {
+        let mut display = blue_display();
+        let area = Rectangle::new(Point::new(i32::MIN, 0), Size::new(u32::MAX, u32::MAX));
+
+        let Ok(()) = display.blended().fill_contiguous(
+            &area,
+            core::iter::repeat_n(Rgba8888::new(255, 0, 0, 128), 1000),
+        );
+
+        // The colors run out before reaching the screen, so nothing is drawn.
+        assert_eq!(display.backend().pixel(0, 0), Some(0x0000FF));
+    }
+
+    #[test]
+    fn fill_contiguous_skips_the_rows_and_columns_off_screen() {
+        let mut display = blue_display();
+        let area = Rectangle::new(Point::new(-2, -3), Size::new(4, 5));
+        // Only the bottom right 2x2 corner is on screen, and only it is opaque.
+        let colors = area.points().map(|point| {
+            if point.x >= 0 && point.y >= 0 {
+                Rgba8888::new(255, 0, 0, 255)
+            } else {
+                Rgba8888::new(0, 255, 0, 255)
+            }
+        });
+
+        let Ok(()) = display.blended().fill_contiguous(&area, colors);
+
+        for point in Rectangle::new(Point::zero(), Size::new(2, 2)).points() {
+            assert_eq!(display.backend().pixel(point.x, point.y), Some(0xFF0000));
+        }
+        assert_eq!(display.backend().pixel(2, 0), Some(0x0000FF));
+        assert_eq!(display.backend().pixel(0, 2), Some(0x0000FF));
+    }
+}
@@ -0,0 +1,72 @@
+//! Rendering automatically once a frame has been drawn.
+
+use core::ops::{Deref, DerefMut};
+
+use crate::{DisplayDriver, PanelBackend};
+
+/// A guard that [renders](DisplayDriver::render) the display when it is dropped.
+///
+/// Created by [`DisplayDriver::auto_render`]. The guard dereferences to the driver, so a frame
+/// can be drawn through it like through the driver itself. In
+/// [`RenderMode::DoubleBuffered`](crate::RenderMode::DoubleBuffered), nothing drawn shows up
+/// until the display is rendered, and forgetting to do so leaves the screen blank; the guard
+/// makes sure the frame is rendered when it goes out of scope, as long as anything was
+/// [drawn](DisplayDriver::is_dirty). In [`RenderMode::Immediate`](crate::RenderMode::Immediate)
+/// draws show up on their own and dropping the guard does nothing.
+///
+/// # Examples
+///
+/// ```
+/// # use vexide::prelude::*;
+/// use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+/// use vexide_embedded_graphics::{DisplayDriver, RenderMode};
+///
+/// # #[vexide::main]
+/// # async fn main(peripherals: Peripherals) {
+/// let mut display = DisplayDriver::new(peripherals.display);
+/// display.set_render_mode(RenderMode::DoubleBuffered);
+///
+/// loop {
+///     let mut frame = display.auto_render();
+///     frame.clear(Rgb888::BLACK).unwrap();
+///     // Draw the rest of the frame...
+///
+///     // `frame` is dropped here, which renders it.
+/// }
+/// # }
+/// ```
+#[must_use = "the frame is rendered when the guard is dropped"]
+pub struct AutoRender<'a, C, B: PanelBackend> {
+    driver: &'a mut DisplayDriver<C, B>,
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Returns a guard that renders the display when it is dropped, if anything was drawn.
+    ///
+    /// See [`AutoRender`].
+    pub fn auto_render(&mut self) -> AutoRender<'_, C, B> {
+        AutoRender { driver: self }
+    }
+}
+
+impl<C, B: PanelBackend> Deref for AutoRender<'_, C, B> {
+    type Target = DisplayDriver<C, B>;
+
+    fn deref(&self) -> &Self::Target {
+        self.driver
+    }
+}
+
+impl<C, B: PanelBackend> DerefMut for AutoRender<'_, C, B> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.driver
+    }
+}
+
+impl<C, B: PanelBackend> Drop for AutoRender<'_, C, B> {
+    fn drop(&mut self) {
+        if self.driver.is_double_buffered() {
+            self.driver.render_if_dirty();
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! The boundary between [`DisplayDriver`](crate::DisplayDriver) and the hardware it draws to.
+//!
+//! Everything the driver needs from vexide and the VEX SDK goes through the [`PanelBackend`]
+//! trait, which only uses this crate's own types, like [`RenderMode`] and [`TouchEvent`]. A
+//! change in vexide's display types only needs to be handled in the implementation for
+//! [`Display`], rather than in every drawing method or in code that uses the driver.
+
+use std::ffi::CStr;
+
+use embedded_graphics_core::geometry::Point;
+use vex_sdk::{
+    vexDisplayCopyRect, vexDisplayFontNamedSet, vexDisplayForegroundColor, vexDisplayPixelSet,
+    vexDisplayPrintf, vexDisplayRectFill, vexDisplayRender, vexDisplayStringWidthGet,
+    vexDisplayTextSize,
+};
+use vexide::{
+    display::{self, Display},
+    time::sleep,
+};
+
+/// How draws get to the panel.
+///
+/// See [`DisplayDriver::set_render_mode`](crate::DisplayDriver::set_render_mode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RenderMode {
+    /// Draws show up on the panel as soon as they are made.
+    #[default]
+    Immediate,
+
+    /// Draws go to a back buffer, which is shown all at once each time the display is
+    /// [rendered](crate::DisplayDriver::render).
+    DoubleBuffered,
+}
+
+/// Whether the touchscreen is being touched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TouchState {
+    /// The screen isn't being touched.
+    Released,
+
+    /// The screen was just touched.
+    Pressed,
+
+    /// The screen has been touched for longer than a moment.
+    Held,
+}
+
+/// The state of the touchscreen.
+///
+/// See [`DisplayDriver::touch_status`](crate::DisplayDriver::touch_status).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TouchEvent {
+    /// Whether the screen is being touched.
+    pub state: TouchState,
+
+    /// Where the screen was last touched, in screen coordinates.
+    pub point: Point,
+
+    /// How many times the screen has been pressed since the brain started.
+    pub press_count: i32,
+
+    /// How many times the screen has been released since the brain started.
+    pub release_count: i32,
+}
+
+pub(crate) mod sealed {
+    /// Keeps [`PanelBackend`](super::PanelBackend) from being implemented outside this crate.
+    pub trait Sealed {}
+}
+
+/// A panel that a [`DisplayDriver`](crate::DisplayDriver) can draw to.
+///
+/// The methods mirror the VEX SDK's display calls. Colors are in the SDK's native `0x00RRGGBB`
+/// format, coordinates are in the SDK's coordinate space, and rectangle corners are inclusive.
+///
+/// This is implemented for vexide's [`Display`] peripheral, which is what you'll want to use on a
+/// real brain, and, with the `mock` feature, for `MockPanel`, which draws into memory instead. The
+/// trait is sealed, so it can't be implemented for other types, which lets it change along with
+/// vexide without breaking code that uses the driver.
+pub trait PanelBackend: sealed::Sealed {
+    /// Sets the rendering mode of the panel.
+    fn set_render_mode(&mut self, mode: RenderMode);
+
+    /// Returns the current rendering mode of the panel.
+    fn render_mode(&self) -> RenderMode;
+
+    /// Flushes the panel's double buffer if [`RenderMode::DoubleBuffered`] is enabled.
+    fn render(&mut self);
+
+    /// Flushes the panel like [`render`](Self::render), then waits until everything drawn so far
+    /// is visible on the panel.
+    fn render_and_wait(&mut self) -> impl Future<Output = ()>;
+
+    /// Returns the last recorded state of the touchscreen.
+    fn touch_status(&self) -> TouchEvent;
+
+    /// Sets the color used by [`set_pixel`](Self::set_pixel) and [`fill_rect`](Self::fill_rect).
+    fn set_foreground_color(&mut self, color: u32);
+
+    /// Sets a single pixel to the foreground color.
+    fn set_pixel(&mut self, x: u32, y: u32);
+
+    /// Fills the rectangle from `(x1, y1)` to `(x2, y2)` with the foreground color.
+    ///
+    /// Both corners are inclusive, like the SDK's, so `(x, y)` to `(x, y)` fills a single pixel.
+    fn fill_rect(&mut self, x1: i32, y1: i32, x2: i32, y2: i32);
+
+    /// Copies `buffer` into the rectangle from `(x1, y1)` to `(x2, y2)`, both inclusive.
+    ///
+    /// `stride` is the number of pixels between the start of each row in `buffer`.
+    fn copy_rect(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, buffer: &[u32], stride: i32);
+
+    /// Draws `text` in the foreground color with its top left corner at `(x, y)`, in the brain's
+    /// built-in monospace font at its default size. The background is left as it is.
+    fn draw_text(&mut self, x: i32, y: i32, text: &CStr);
+
+    /// Returns how many pixels wide `text` is in the font used by [`draw_text`](Self::draw_text).
+    fn text_width(&self, text: &CStr) -> i32;
+}
+
+/// Sets the SDK's font to the one used by [`PanelBackend::draw_text`].
+///
+/// The font is global SDK state, and vexide's own text drawing changes it, so it's set again
+/// before each use.
+fn apply_builtin_font() {
+    unsafe {
+        vexDisplayFontNamedSet(c"monospace".as_ptr());
+        vexDisplayTextSize(1, 3);
+    }
+}
+
+impl sealed::Sealed for Display {}
+
+impl PanelBackend for Display {
+    fn set_render_mode(&mut self, mode: RenderMode) {
+        Display::set_render_mode(
+            self,
+            match mode {
+                RenderMode::Immediate => display::RenderMode::Immediate,
+                RenderMode::DoubleBuffered => display::RenderMode::DoubleBuffered,
+            },
+        );
+    }
+
+    fn render_mode(&self) -> RenderMode {
+        match Display::render_mode(self) {
+            display::RenderMode::Immediate => RenderMode::Immediate,
+            display::RenderMode::DoubleBuffered => RenderMode::DoubleBuffered,
+        }
+    }
+
+    fn render(&mut self) {
+        Display::render(self);
+    }
+
+    async fn render_and_wait(&mut self) {
+        match PanelBackend::render_mode(self) {
+            // Ask the SDK to wait for vertical sync before swapping buffers. This blocks inside
+            // the SDK, but only until the next refresh.
+            RenderMode::DoubleBuffered => unsafe { vexDisplayRender(true, false) },
+            // There's no completion signal for immediate draws, so wait a full refresh to be sure
+            // the panel has scanned them out, sleeping so other tasks keep running meanwhile.
+            RenderMode::Immediate => sleep(Display::REFRESH_INTERVAL).await,
+        }
+    }
+
+    fn touch_status(&self) -> TouchEvent {
+        let touch = Display::touch_status(self);
+        TouchEvent {
+            state: match touch.state {
+                display::TouchState::Released => TouchState::Released,
+                display::TouchState::Pressed => TouchState::Pressed,
+                display::TouchState::Held => TouchState::Held,
+            },
+            point: Point::new(touch.point.x.into(), touch.point.y.into()),
+            press_count: touch.press_count,
+            release_count: touch.release_count,
+        }
+    }
+
+    fn set_foreground_color(&mut self, color: u32) {
+        unsafe {
+            vexDisplayForegroundColor(color);
+        }
+    }
+
+    fn set_pixel(&mut self, x: u32, y: u32) {
+        unsafe {
+            vexDisplayPixelSet(x, y);
+        }
+    }
+
+    fn fill_rect(&mut self, x1: i32, y1: i32, x2: i32, y2: i32) {
+        unsafe {
+            vexDisplayRectFill(x1, y1, x2, y2);
+        }
+    }
+
+    fn copy_rect(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, buffer: &[u32], stride: i32) {
+        // The SDK takes a mutable pointer, but only ever reads from the buffer.
+        unsafe {
+            vexDisplayCopyRect(x1, y1, x2, y2, buffer.as_ptr().cast_mut(), stride);
+        }
+    }
+
+    fn draw_text(&mut self, x: i32, y: i32, text: &CStr) {
+        apply_builtin_font();
+        unsafe {
+            vexDisplayPrintf(x, y, 0, c"%s".as_ptr(), text.as_ptr());
+        }
+    }
+
+    fn text_width(&self, text: &CStr) -> i32 {
+        apply_builtin_font();
+        unsafe { vexDisplayStringWidthGet(text.as_ptr()) }
+    }
+}
@@ -0,0 +1,88 @@
+//! The background color that areas are erased to.
+
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use crate::{DisplayDriver, PanelBackend, ToDisplayStorage};
+
+impl<C: ToDisplayStorage, B: PanelBackend> DisplayDriver<C, B> {
+    /// Sets the color that [`clear_widget_area`](Self::clear_widget_area) erases to.
+    ///
+    /// The background is black until this is called, or the color passed to
+    /// [`with_clear`](Self::with_clear) if the driver was created with it. Nothing is redrawn
+    /// when the background changes.
+    pub fn set_background_color(&mut self, color: C) {
+        self.background = color.to_display_storage();
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Fills `area` with the [background color](Self::set_background_color).
+    ///
+    /// This is the erase step for widgets that redraw themselves in place, so they don't each
+    /// need to know the background. `area` is in logical coordinates and clipped to the drawable
+    /// area like any other fill.
+    pub fn clear_widget_area(&mut self, area: &Rectangle) {
+        self.fill_solid_storage(area, self.background);
+    }
+
+    /// Clears the whole drawable area to black.
+    ///
+    /// This is [`clear`](DrawTarget::clear) with black, for the most common clear at the start of
+    /// a frame, whatever the driver's color type. Like `clear`, it fills the screen with a single
+    /// SDK call (or one per part around a [managed region](Self::set_managed_region)). The SDK's
+    /// own `vexDisplayErase` isn't used, since it erases to the SDK's background color without
+    /// regard for the drawable area, and would leave the shadow buffer out of date.
+    pub fn clear_black(&mut self) {
+        self.fill_solid_storage(&self.bounding_box(), 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::pixelcolor::Rgb888;
+
+    use super::*;
+    use crate::MockPanel;
+
+    #[test]
+    fn clear_widget_area_fills_with_the_background_color() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.enable_shadow_buffer();
+        display.clear(Rgb888::RED).unwrap();
+        display.set_background_color(Rgb888::BLUE);
+
+        display.clear_widget_area(&Rectangle::new(Point::new(-5, 10), Size::new(15, 5)));
+
+        let panel = display.backend();
+        assert_eq!(panel.pixel(0, 10), Some(0x0000FF));
+        assert_eq!(panel.pixel(9, 14), Some(0x0000FF));
+        assert_eq!(panel.pixel(10, 14), Some(0xFF0000));
+        assert_eq!(panel.pixel(0, 15), Some(0xFF0000));
+        assert_eq!(panel.fill_rects().len(), 2);
+        assert_eq!(display.pixel_at(Point::new(0, 10)), Some(Rgb888::BLUE));
+    }
+
+    #[test]
+    fn the_background_starts_out_black() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.clear(Rgb888::RED).unwrap();
+
+        display.clear_widget_area(&Rectangle::new(Point::zero(), Size::new(2, 2)));
+
+        assert_eq!(display.backend().pixel(1, 1), Some(0));
+        assert_eq!(display.backend().pixel(2, 2), Some(0xFF0000));
+    }
+
+    #[test]
+    fn clear_black_clears_the_drawable_area() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.clear(Rgb888::RED).unwrap();
+        display.set_origin(Point::new(10, 0));
+
+        display.clear_black();
+
+        assert_eq!(display.backend().pixel(9, 0), Some(0xFF0000));
+        assert_eq!(display.backend().pixel(10, 0), Some(0));
+        assert_eq!(display.backend().pixel(479, 239), Some(0));
+    }
+}
@@ -0,0 +1,93 @@
+//! Drawing monochrome art in two configurable colors.
+
+use embedded_graphics_core::{pixelcolor::BinaryColor, prelude::*, primitives::Rectangle};
+
+use crate::{DisplayDriver, DisplayError, PanelBackend, ToDisplayStorage};
+
+/// A draw target for [`BinaryColor`] art, which draws [`BinaryColor::On`] and
+/// [`BinaryColor::Off`] in the driver's [binary colors](DisplayDriver::set_binary_colors).
+///
+/// Many fonts and icons for embedded-graphics are authored in [`BinaryColor`]. This draws them in
+/// any two colors without mapping each pixel by hand; a driver created for [`BinaryColor`] instead
+/// always draws white and black.
+///
+/// Created with [`DisplayDriver::binary`].
+pub struct BinaryDrawTarget<'a, C, B: PanelBackend> {
+    driver: &'a mut DisplayDriver<C, B>,
+}
+
+impl<C: ToDisplayStorage, B: PanelBackend> DisplayDriver<C, B> {
+    /// Sets the colors that [`BinaryColor::On`] and [`BinaryColor::Off`] are drawn in by the
+    /// [`binary`](Self::binary) draw target.
+    ///
+    /// They are white and black until this is called. Nothing already drawn is changed.
+    pub fn set_binary_colors(&mut self, on: C, off: C) {
+        self.binary_colors = (on.to_display_storage(), off.to_display_storage());
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Returns a draw target for drawing [`BinaryColor`] art in the
+    /// [binary colors](Self::set_binary_colors).
+    ///
+    /// See [`BinaryDrawTarget`].
+    pub fn binary(&mut self) -> BinaryDrawTarget<'_, C, B> {
+        BinaryDrawTarget { driver: self }
+    }
+}
+
+impl<C, B: PanelBackend> OriginDimensions for BinaryDrawTarget<'_, C, B> {
+    fn size(&self) -> Size {
+        self.driver.size()
+    }
+}
+
+impl<C, B: PanelBackend> DrawTarget for BinaryDrawTarget<'_, C, B> {
+    type Color = BinaryColor;
+
+    type Error = DisplayError;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(pos, color) in pixels {
+            if let Some(pos) = self.driver.to_screen(pos) {
+                self.driver
+                    .write_pixel(pos, storage(self.driver.binary_colors, color));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let binary_colors = self.driver.binary_colors;
+        let colors = colors
+            .into_iter()
+            .map(|color| storage(binary_colors, color));
+        if self.driver.fill_contiguous_storage(area, colors) {
+            Ok(())
+        } else {
+            Err(DisplayError::ColorBufferTooSmall)
+        }
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.driver
+            .fill_solid_storage(area, storage(self.driver.binary_colors, color));
+
+        Ok(())
+    }
+}
+
+/// Returns the color `color` is drawn in, given the `(on, off)` binary colors.
+const fn storage((on, off): (u32, u32), color: BinaryColor) -> u32 {
+    match color {
+        BinaryColor::On => on,
+        BinaryColor::Off => off,
+    }
+}
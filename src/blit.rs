@@ -0,0 +1,460 @@
+//! Copying images onto the display.
+
+use embedded_graphics::draw_target::DrawTargetExt;
+use embedded_graphics_core::{
+    image::ImageDrawable,
+    pixelcolor::{Rgb565, Rgb888, raw::RawU16},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use crate::{
+    DisplayDriver, DisplayError, Insets, PanelBackend, ToDisplayStorage, frame::safe_intersection,
+};
+
+/// How an image is split up by [`DisplayDriver::draw_nine_patch`].
+///
+/// The borders are measured in source pixels from each edge of the image. The four corners they
+/// cut off are drawn at their original size, the edges between the corners are stretched along
+/// their length, and the center is stretched in both directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NinePatch {
+    /// Size of the fixed border on each edge of the source image.
+    pub borders: Insets,
+}
+
+impl NinePatch {
+    /// Creates a new nine-patch with the given borders.
+    #[must_use]
+    pub const fn new(borders: Insets) -> Self {
+        Self { borders }
+    }
+}
+
+impl<C: ToDisplayStorage, B: PanelBackend> DisplayDriver<C, B> {
+    /// Draws an image with its top left corner at `top_left`.
+    ///
+    /// `src` holds the image's pixels in row-major order, `src_width` pixels per row. A trailing
+    /// partial row is ignored. Parts of the image outside the display are clipped.
+    pub fn blit(&mut self, top_left: Point, src: &[C], src_width: u32) {
+        let area = Rectangle::new(top_left, image_size(src, src_width));
+        self.fill_contiguous_storage(&area, src.iter().map(|color| color.to_display_storage()));
+    }
+
+    /// Fills `area` with `colors`, in row-major order, straight from a slice.
+    ///
+    /// This is [`fill_contiguous`](DrawTarget::fill_contiguous) for colors that are already in
+    /// memory, like the data behind an `ImageRaw`. The iterator that `fill_contiguous` takes has
+    /// to be stepped through one color at a time, including all of the colors for parts of
+    /// `area` that are off screen. This instead reads the visible part of each row straight out
+    /// of the slice, so clipping costs nothing. Each color still has to be converted to the
+    /// SDK's 32-bit format on the way; an [`OffscreenCanvas`](crate::OffscreenCanvas), which
+    /// already stores that format, can be [presented](Self::present) without any conversion.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DisplayError::ColorBufferTooSmall`] if `colors` has fewer colors than `area`
+    /// has pixels, in which case nothing is drawn.
+    pub fn blit_slice(&mut self, area: &Rectangle, colors: &[C]) -> Result<(), DisplayError> {
+        let stride = area.size.width as usize;
+        if stride
+            .checked_mul(area.size.height as usize)
+            .is_none_or(|len| colors.len() < len)
+        {
+            return Err(DisplayError::ColorBufferTooSmall);
+        }
+
+        self.blit_converted(area, colors, stride, |color| color.to_display_storage());
+        Ok(())
+    }
+
+    /// Draws an embedded-graphics image, like an `ImageRaw`, with its top left corner at
+    /// `top_left`.
+    ///
+    /// Drawing an image the usual way, with `Image::new(&image, top_left).draw(&mut display)`,
+    /// decodes all of it even when most of it is off screen. This only decodes the part that is
+    /// visible, and copies it to the display a row at a time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DisplayError::ColorBufferTooSmall`] if the image has less data than its size
+    /// needs.
+    pub fn draw_image<I>(&mut self, top_left: Point, image: &I) -> Result<(), DisplayError>
+    where
+        I: ImageDrawable<Color = C>,
+    {
+        let visible = safe_intersection(
+            &Rectangle::new(top_left, image.size()),
+            &self.bounding_box(),
+        );
+        if visible.is_zero_sized() {
+            return Ok(());
+        }
+
+        let src = Rectangle::new(visible.top_left - top_left, visible.size);
+        image.draw_sub_image(&mut self.translated(visible.top_left), &src)
+    }
+
+    /// Draws an embedded-graphics image like [`draw_image`](Self::draw_image), but leaves the
+    /// pixels that are `key` as they were, so a sprite can be drawn over a background with a
+    /// color standing in for transparency.
+    ///
+    /// Each run of other pixels in a row is copied to the display with one SDK call, so rows
+    /// without any `key` pixels cost the same as with `draw_image`. Pick a key the rest of the
+    /// image never uses, like bright magenta; pixels are compared after conversion to the SDK's
+    /// 32-bit format, so colors that convert to the key's value are left out too.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DisplayError::ColorBufferTooSmall`] if the image has less data than its size
+    /// needs.
+    pub fn draw_image_keyed<I>(
+        &mut self,
+        top_left: Point,
+        image: &I,
+        key: C,
+    ) -> Result<(), DisplayError>
+    where
+        I: ImageDrawable<Color = C>,
+    {
+        let visible = safe_intersection(
+            &Rectangle::new(top_left, image.size()),
+            &self.bounding_box(),
+        );
+        if visible.is_zero_sized() {
+            return Ok(());
+        }
+
+        let src = Rectangle::new(visible.top_left - top_left, visible.size);
+        let mut keyed = Keyed {
+            driver: self,
+            key: key.to_display_storage(),
+        };
+        image.draw_sub_image(&mut keyed.translated(visible.top_left), &src)
+    }
+
+    /// Draws an image stretched or shrunk to fill `area`.
+    ///
+    /// `src` is laid out the same way as for [`blit`](Self::blit). Scaling uses nearest-neighbor
+    /// sampling, which keeps pixel art sharp and is cheap enough to do every frame.
+    pub fn blit_scaled(&mut self, area: &Rectangle, src: &[C], src_width: u32) {
+        let region = Rectangle::new(Point::zero(), image_size(src, src_width));
+        self.blit_region(area, src, src_width, &region);
+    }
+
+    /// Draws an image as a [nine-patch](NinePatch), resized to fill `area`.
+    ///
+    /// This lets a single small image be used for buttons and panels of any size without
+    /// stretching their decorated corners. `src` is laid out the same way as for
+    /// [`blit`](Self::blit). If `area` is too small to fit two corners side by side, they are
+    /// shrunk to fit. If the borders take up the whole source, so it has no middle to stretch,
+    /// the inner edges of the borders are stretched halfway across the middle of `area` each.
+    pub fn draw_nine_patch(
+        &mut self,
+        area: &Rectangle,
+        patch: &NinePatch,
+        src: &[C],
+        src_width: u32,
+    ) {
+        let src_size = image_size(src, src_width);
+        let borders = patch.borders;
+
+        let src_columns = split_source(src_size.width, borders.left, borders.right);
+        let src_rows = split_source(src_size.height, borders.top, borders.bottom);
+        let dest_columns = split_dest(area.size.width, src_columns.0, src_columns.2);
+        let dest_rows = split_dest(area.size.height, src_rows.0, src_rows.2);
+
+        for (src_y, region_height, dest_y, dest_height) in spans(src_rows, dest_rows) {
+            for (src_x, region_width, dest_x, dest_width) in spans(src_columns, dest_columns) {
+                let region = Rectangle::new(
+                    Point::new(src_x as i32, src_y as i32),
+                    Size::new(region_width, region_height),
+                );
+                let dest = Rectangle::new(
+                    area.top_left + Point::new(dest_x as i32, dest_y as i32),
+                    Size::new(dest_width, dest_height),
+                );
+                self.blit_region(&dest, src, src_width, &region);
+            }
+        }
+    }
+
+    /// Draws `region` of the source image scaled to fill `dest`.
+    fn blit_region(&mut self, dest: &Rectangle, src: &[C], src_width: u32, region: &Rectangle) {
+        if dest.is_zero_sized() || region.is_zero_sized() {
+            return;
+        }
+
+        let (dest_width, dest_height) = (u64::from(dest.size.width), u64::from(dest.size.height));
+        let (region_width, region_height) =
+            (u64::from(region.size.width), u64::from(region.size.height));
+        let (left, top) = (region.top_left.x as usize, region.top_left.y as usize);
+        let src_width = src_width as usize;
+
+        let colors = (0..dest_height).flat_map(move |y| {
+            let row = (top + (y * region_height / dest_height) as usize) * src_width + left;
+            (0..dest_width).map(move |x| {
+                src[row + (x * region_width / dest_width) as usize].to_display_storage()
+            })
+        });
+        self.fill_contiguous_storage(dest, colors);
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Draws a raw RGB565 image with its top left corner at `top_left`.
+    ///
+    /// Each `u16` in `data` is one pixel, with red in the top 5 bits, green in the middle 6 and
+    /// blue in the bottom 5, like embedded-graphics' [`Rgb565`]. The values are read as native
+    /// `u16`s, so data that arrives as bytes in big-endian order, as many camera modules and image
+    /// formats send it, should be converted with [`u16::from_be_bytes`] first. Pixels are laid
+    /// out in row-major order, `width` per row, and a trailing partial row is ignored.
+    ///
+    /// Only the part of the image that is on screen is read, and each of its rows is expanded to
+    /// the SDK's 32-bit format in the driver's row buffer and copied with one SDK call, so the
+    /// image never has to be converted up front. Colors are expanded the same way as drawing
+    /// [`Rgb565`] colors through the driver.
+    pub fn present_image_565(&mut self, top_left: Point, width: u32, data: &[u16]) {
+        let area = Rectangle::new(top_left, image_size(data, width));
+        self.blit_converted(&area, data, width as usize, |&color| {
+            Rgb888::from(Rgb565::from(RawU16::new(color))).into_storage()
+        });
+    }
+
+    /// Draws `src`, which holds at least enough pixels for the whole of `area` in row-major
+    /// order with rows `stride` pixels apart, converting the visible part of each row with
+    /// `convert` as it is copied.
+    pub(crate) fn blit_converted<T>(
+        &mut self,
+        area: &Rectangle,
+        src: &[T],
+        stride: usize,
+        convert: impl Fn(&T) -> u32,
+    ) {
+        let visible = safe_intersection(area, &self.bounding_box());
+        if visible.is_zero_sized() {
+            return;
+        }
+
+        let width = visible.size.width as usize;
+        let left = visible.top_left.x.abs_diff(area.top_left.x) as usize;
+        for y in visible.rows() {
+            let start = y.abs_diff(area.top_left.y) as usize * stride + left;
+            for (pixel, color) in self.buffer[..width].iter_mut().zip(&src[start..][..width]) {
+                *pixel = convert(color);
+            }
+            self.write_row(Point::new(visible.top_left.x, y), visible.size.width);
+        }
+    }
+}
+
+/// A draw target that leaves pixels of one color out, for
+/// [`draw_image_keyed`](DisplayDriver::draw_image_keyed).
+struct Keyed<'a, C, B: PanelBackend> {
+    driver: &'a mut DisplayDriver<C, B>,
+    key: u32,
+}
+
+impl<C, B: PanelBackend> OriginDimensions for Keyed<'_, C, B> {
+    fn size(&self) -> Size {
+        self.driver.size()
+    }
+}
+
+impl<C: ToDisplayStorage, B: PanelBackend> DrawTarget for Keyed<'_, C, B> {
+    type Color = C;
+
+    type Error = DisplayError;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let key = self.key;
+        self.driver.draw_iter(
+            pixels
+                .into_iter()
+                .filter(|Pixel(_, color)| color.to_display_storage() != key),
+        )
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let colors = colors.into_iter().map(C::to_display_storage);
+        let clip = self.driver.bounding_box();
+        if self
+            .driver
+            .fill_contiguous_keyed(area, &clip, colors, Some(self.key))
+        {
+            Ok(())
+        } else {
+            Err(DisplayError::ColorBufferTooSmall)
+        }
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let color = color.to_display_storage();
+        if color != self.key {
+            self.driver.fill_solid_storage(area, color);
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the size of the whole rows of an image in `src`.
+pub(crate) fn image_size<C>(src: &[C], src_width: u32) -> Size {
+    if src_width == 0 {
+        return Size::zero();
+    }
+
+    Size::new(src_width, (src.len() / src_width as usize) as u32)
+}
+
+/// Splits a source dimension into its start border, middle and end border, clamping the borders
+/// to the image.
+fn split_source(length: u32, start: u32, end: u32) -> (u32, u32, u32) {
+    let start = start.min(length);
+    let end = end.min(length - start);
+    (start, length - start - end, end)
+}
+
+/// Splits a destination dimension into its start border, middle and end border, shrinking the
+/// borders proportionally if they don't fit.
+fn split_dest(length: u32, start: u32, end: u32) -> (u32, u32, u32) {
+    let borders = start + end;
+    if length >= borders {
+        (start, length - borders, end)
+    } else {
+        let start = (u64::from(start) * u64::from(length) / u64::from(borders)) as u32;
+        (start, 0, length - start)
+    }
+}
+
+/// Pairs up the source and destination spans of a split dimension as
+/// `(src_offset, src_length, dest_offset, dest_length)`.
+///
+/// A source without a middle fills the destination's middle from the borders' inner edges, the
+/// first half from the start border and the rest from the end border, so it isn't left unpainted.
+fn spans(
+    src: (u32, u32, u32),
+    dest: (u32, u32, u32),
+) -> impl Iterator<Item = (u32, u32, u32, u32)> {
+    let middle = if src.1 > 0 || src.0 + src.2 == 0 {
+        [
+            (src.0, src.1, dest.0, dest.1),
+            (src.0, 0, dest.0 + dest.1, 0),
+        ]
+    } else {
+        let half = match (src.0, src.2) {
+            (0, _) => 0,
+            (_, 0) => dest.1,
+            _ => dest.1 / 2,
+        };
+        [
+            (src.0.saturating_sub(1), 1, dest.0, half),
+            (src.0, 1, dest.0 + half, dest.1 - half),
+        ]
+    };
+
+    [(0, src.0, 0, dest.0)].into_iter().chain(middle).chain([(
+        src.0 + src.1,
+        src.2,
+        dest.0 + dest.1,
+        dest.2,
+    )])
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::pixelcolor::raw::RawU24;
+
+    use super::*;
+    use crate::MockPanel;
+
+    /// A 3x3 image with a different color in each pixel, numbered 1 to 9 in row-major order.
+    fn patch_3x3() -> [Rgb888; 9] {
+        core::array::from_fn(|i| Rgb888::from(RawU24::new(i as u32 + 1)))
+    }
+
+    #[test]
+    fn nine_patch_corners_stay_unstretched() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let area = Rectangle::new(Point::new(5, 5), Size::new(10, 8));
+
+        display.draw_nine_patch(&area, &NinePatch::new(Insets::uniform(1)), &patch_3x3(), 3);
+
+        let panel = display.backend();
+        assert_eq!(panel.pixel(5, 5), Some(1));
+        assert_eq!(panel.pixel(14, 5), Some(3));
+        assert_eq!(panel.pixel(5, 12), Some(7));
+        assert_eq!(panel.pixel(14, 12), Some(9));
+
+        // The edges next to each corner are already stretched.
+        assert_eq!(panel.pixel(6, 5), Some(2));
+        assert_eq!(panel.pixel(13, 5), Some(2));
+        assert_eq!(panel.pixel(5, 6), Some(4));
+        assert_eq!(panel.pixel(14, 11), Some(6));
+        assert!((6..14).all(|x| (6..12).all(|y| panel.pixel(x, y) == Some(5))));
+        assert_eq!(panel.pixel(4, 5), Some(0));
+        assert_eq!(panel.pixel(15, 12), Some(0));
+    }
+
+    #[test]
+    fn nine_patch_borders_shrink_when_the_area_is_too_small() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let area = Rectangle::new(Point::zero(), Size::new(2, 1));
+
+        display.draw_nine_patch(&area, &NinePatch::new(Insets::uniform(1)), &patch_3x3(), 3);
+
+        let panel = display.backend();
+        assert_eq!(panel.pixel(0, 0), Some(7));
+        assert_eq!(panel.pixel(1, 0), Some(9));
+        assert_eq!(panel.pixel(0, 1), Some(0));
+    }
+
+    #[test]
+    fn nine_patch_without_a_middle_stretches_the_border_edges() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.clear(Rgb888::WHITE).unwrap();
+        let area = Rectangle::new(Point::zero(), Size::new(6, 5));
+        // The left and top borders are a pixel wide and the others two, covering the whole image.
+        let patch = NinePatch::new(Insets::new(1, 2, 2, 1));
+
+        display.draw_nine_patch(&area, &patch, &patch_3x3(), 3);
+
+        // Of the three middle columns, the first comes from the left border's and the other two
+        // from the right border's inner edge. The two middle rows are split the same way.
+        let columns = [0, 0, 1, 1, 1, 2];
+        let rows = [0, 0, 1, 1, 2];
+        let panel = display.backend();
+        for (y, &row) in rows.iter().enumerate() {
+            for (x, &column) in columns.iter().enumerate() {
+                let expected = row * 3 + column + 1;
+                assert_eq!(
+                    panel.pixel(x as i32, y as i32),
+                    Some(expected),
+                    "({x}, {y})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn nine_patch_of_only_borders_scales_like_the_whole_image() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let src = [Rgb888::RED, Rgb888::GREEN, Rgb888::BLUE, Rgb888::YELLOW];
+
+        let area = Rectangle::new(Point::zero(), Size::new(4, 4));
+        display.draw_nine_patch(&area, &NinePatch::new(Insets::uniform(1)), &src, 2);
+
+        let mut expected = DisplayDriver::new(MockPanel::new());
+        expected.blit_scaled(&area, &src, 2);
+        assert!(
+            display.backend().framebuffer() == expected.backend().framebuffer(),
+            "every pixel of the middle should be painted"
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Reading back what's on the screen, as raw pixels or a BMP image.
+
+use std::io::{self, Write};
+
+use embedded_graphics_core::{
+    pixelcolor::{Rgb888, raw::RawU24},
+    prelude::*,
+};
+
+use crate::{DisplayDriver, DisplayError, OffscreenCanvas, PanelBackend, WIDTH};
+
+/// Size of the BMP file header plus the `BITMAPINFOHEADER` that follows it.
+const HEADER_SIZE: u32 = 14 + 40;
+
+impl<C, B> DisplayDriver<C, B> {
+    /// Writes the contents of the screen to `writer` as a 24-bit BMP image.
+    ///
+    /// The display can't be read back from, so this saves the
+    /// [shadow buffer](Self::enable_shadow_buffer) rather than what's actually on the panel. The
+    /// image covers the whole area below the header, regardless of the origin and insets.
+    ///
+    /// There's no file system support in this crate, so `writer` can be anything from a file on
+    /// the SD card to an in-memory `Vec<u8>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from `writer`, or an error wrapping [`DisplayError::NoShadowBuffer`] if
+    /// the shadow buffer isn't enabled.
+    pub fn write_bmp<W: Write>(&self, writer: W) -> io::Result<()> {
+        let shadow = self
+            .shadow
+            .as_deref()
+            .ok_or_else(|| io::Error::other(DisplayError::NoShadowBuffer))?;
+
+        encode(writer, WIDTH, self.screen_height(), shadow)
+    }
+
+    /// Returns the contents of the screen as raw 24-bit RGB pixels.
+    ///
+    /// Like [`write_bmp`](Self::write_bmp), this is a copy of the
+    /// [shadow buffer](Self::enable_shadow_buffer), covering the whole area below the header
+    /// regardless of the origin and insets. Pixels are in row-major order, three bytes each, and
+    /// each row is as wide as the display. That's the layout embedded-graphics' `ImageRaw` reads,
+    /// so a screenshot can be drawn somewhere else or compared against an expected image:
+    ///
+    /// ```
+    /// # use vexide::{display::Display, prelude::*};
+    /// use embedded_graphics::{image::ImageRaw, pixelcolor::Rgb888};
+    /// use vexide_embedded_graphics::DisplayDriver;
+    ///
+    /// # #[vexide::main]
+    /// # async fn main(peripherals: Peripherals) {
+    /// let mut display = DisplayDriver::new(peripherals.display);
+    /// display.enable_shadow_buffer();
+    /// // Draw something...
+    ///
+    /// let pixels = display.screenshot().unwrap();
+    /// let image = ImageRaw::<Rgb888>::new(&pixels, Display::HORIZONTAL_RESOLUTION as u32);
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DisplayError::NoShadowBuffer`] if the shadow buffer isn't enabled.
+    pub fn screenshot(&self) -> Result<Vec<u8>, DisplayError> {
+        let shadow = self.shadow.as_deref().ok_or(DisplayError::NoShadowBuffer)?;
+
+        Ok(shadow
+            .iter()
+            .flat_map(|color| {
+                let [_, r, g, b] = color.to_be_bytes();
+                [r, g, b]
+            })
+            .collect())
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Returns the color last drawn at `point`, in embedded-graphics coordinates.
+    ///
+    /// Like [`screenshot`](Self::screenshot), this reads the
+    /// [shadow buffer](Self::enable_shadow_buffer), so it reflects every fill, blit and pixel
+    /// drawn through the driver since the buffer was enabled, before any
+    /// [brightness](Self::set_brightness) or [gamma](Self::set_gamma) is applied. Returns `None`
+    /// if `point` is outside the drawable area or the shadow buffer isn't enabled.
+    #[must_use]
+    pub fn pixel_at(&self, point: Point) -> Option<Rgb888> {
+        self.stored_pixel(point)
+            .map(|color| Rgb888::from(RawU24::new(color)))
+    }
+}
+
+impl OffscreenCanvas {
+    /// Writes the canvas to `writer` as a 24-bit BMP image.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from `writer`.
+    pub fn write_bmp<W: Write>(&self, writer: W) -> io::Result<()> {
+        let size = self.size();
+        encode(writer, size.width, size.height, self.pixels())
+    }
+}
+
+/// Writes `width` by `height` pixels in the display's native format, stored top to bottom, as a
+/// BMP image.
+fn encode<W: Write>(mut writer: W, width: u32, height: u32, pixels: &[u32]) -> io::Result<()> {
+    // Each row of pixels is padded to a multiple of four bytes.
+    let row_size = (width * 3).next_multiple_of(4);
+    let image_size = row_size * height;
+
+    let mut header = Vec::with_capacity(HEADER_SIZE as usize);
+    // File header
+    header.extend_from_slice(b"BM");
+    header.extend_from_slice(&(HEADER_SIZE + image_size).to_le_bytes());
+    header.extend_from_slice(&[0; 4]);
+    header.extend_from_slice(&HEADER_SIZE.to_le_bytes());
+    // BITMAPINFOHEADER
+    header.extend_from_slice(&40u32.to_le_bytes());
+    header.extend_from_slice(&(width as i32).to_le_bytes());
+    header.extend_from_slice(&(height as i32).to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes());
+    header.extend_from_slice(&24u16.to_le_bytes());
+    header.extend_from_slice(&0u32.to_le_bytes()); // No compression
+    header.extend_from_slice(&image_size.to_le_bytes());
+    header.extend_from_slice(&2835i32.to_le_bytes()); // 72 DPI
+    header.extend_from_slice(&2835i32.to_le_bytes());
+    header.extend_from_slice(&0u32.to_le_bytes()); // No palette
+    header.extend_from_slice(&0u32.to_le_bytes());
+    writer.write_all(&header)?;
+
+    if width == 0 {
+        return Ok(());
+    }
+
+    // Rows are stored bottom to top, with each pixel in BGR order.
+    let mut row = vec![0; row_size as usize];
+    for pixels in pixels
+        .chunks_exact(width as usize)
+        .take(height as usize)
+        .rev()
+    {
+        for (bytes, &pixel) in row.chunks_exact_mut(3).zip(pixels) {
+            let [_, r, g, b] = pixel.to_be_bytes();
+            bytes.copy_from_slice(&[b, g, r]);
+        }
+        writer.write_all(&row)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::primitives::Rectangle;
+
+    use super::*;
+    use crate::MockPanel;
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..][..4].try_into().unwrap())
+    }
+
+    /// Reads the pixel at `(x, y)`, counted from the top, out of a 24-bit BMP image.
+    fn bmp_pixel(bmp: &[u8], x: u32, y: u32) -> u32 {
+        let (width, height) = (u32_at(bmp, 18), u32_at(bmp, 22));
+        let row_size = (width * 3).next_multiple_of(4);
+        let start = (u32_at(bmp, 10) + (height - 1 - y) * row_size + x * 3) as usize;
+        let [b, g, r] = bmp[start..][..3].try_into().unwrap();
+        u32::from_be_bytes([0, r, g, b])
+    }
+
+    #[test]
+    fn write_bmp_writes_a_24_bit_header() {
+        let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+        display.enable_shadow_buffer();
+
+        let mut bmp = Vec::new();
+        display.write_bmp(&mut bmp).unwrap();
+
+        assert_eq!(&bmp[..2], b"BM");
+        assert_eq!(u32_at(&bmp, 2) as usize, bmp.len());
+        assert_eq!(bmp.len(), 54 + 480 * 3 * 240);
+        assert_eq!(u32_at(&bmp, 10), 54);
+        assert_eq!(u32_at(&bmp, 14), 40);
+        assert_eq!((u32_at(&bmp, 18), u32_at(&bmp, 22)), (480, 240));
+        assert_eq!(u16::from_le_bytes([bmp[26], bmp[27]]), 1);
+        assert_eq!(u16::from_le_bytes([bmp[28], bmp[29]]), 24);
+        assert_eq!(u32_at(&bmp, 30), 0);
+    }
+
+    #[test]
+    fn write_bmp_pixels_round_trip() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.enable_shadow_buffer();
+        display.set_pixel(Point::new(0, 0), Rgb888::new(0x12, 0x34, 0x56));
+        display.set_pixel(Point::new(479, 239), Rgb888::RED);
+        display.set_pixel(Point::new(100, 50), Rgb888::BLUE);
+
+        let mut bmp = Vec::new();
+        display.write_bmp(&mut bmp).unwrap();
+
+        assert_eq!(bmp_pixel(&bmp, 0, 0), 0x123456);
+        assert_eq!(bmp_pixel(&bmp, 479, 239), 0xFF0000);
+        assert_eq!(bmp_pixel(&bmp, 100, 50), 0x0000FF);
+        assert_eq!(bmp_pixel(&bmp, 101, 50), 0);
+    }
+
+    #[test]
+    fn rows_are_padded_to_four_bytes() {
+        let mut canvas = OffscreenCanvas::new(Size::new(3, 2));
+        let Ok(()) = canvas.fill_solid(
+            &Rectangle::new(Point::new(2, 1), Size::new(1, 1)),
+            Rgb888::GREEN,
+        );
+
+        let mut bmp = Vec::new();
+        canvas.write_bmp(&mut bmp).unwrap();
+
+        assert_eq!(bmp.len(), 54 + 12 * 2);
+        assert_eq!(bmp_pixel(&bmp, 2, 1), 0x00FF00);
+        assert_eq!(bmp_pixel(&bmp, 2, 0), 0);
+    }
+
+    #[test]
+    fn write_bmp_needs_the_shadow_buffer() {
+        let display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+
+        let error = display.write_bmp(Vec::new()).unwrap_err();
+
+        let inner = error
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<DisplayError>());
+        assert_eq!(inner, Some(&DisplayError::NoShadowBuffer));
+        assert_eq!(display.screenshot(), Err(DisplayError::NoShadowBuffer));
+    }
+}
@@ -0,0 +1,71 @@
+//! Fast text drawn with the brain's built-in font.
+
+use std::ffi::CString;
+
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use crate::{DisplayDriver, PanelBackend, ToDisplayStorage};
+
+/// Height of a line of built-in text, which matches the line spacing VEXos uses for its own
+/// text output.
+const LINE_HEIGHT: u32 = 20;
+
+impl<C: ToDisplayStorage, B: PanelBackend> DisplayDriver<C, B> {
+    /// Draws `text` in the brain's built-in monospace font, on line `row` starting at character
+    /// cell `col`.
+    ///
+    /// embedded-graphics text is drawn a pixel at a time, which is slow for readouts that change
+    /// every frame. This hands the whole string to the SDK instead, which renders it in firmware.
+    /// It's meant for debug output: there is no choice of font or size, and only the glyphs are
+    /// drawn, so clear the line first when replacing text that was longer.
+    ///
+    /// The text is laid out on a grid of cells. Rows are 20 pixels tall, the same spacing VEXos
+    /// uses for its own text, and columns are as wide as one character of the font, so row 0,
+    /// col 0 is the top left of the drawable area (which respects the [header
+    /// offset](Self::with_header_offset), [origin](Self::set_origin) and
+    /// [insets](Self::set_insets)). Rows and columns past the edge of the screen aren't drawn,
+    /// and the SDK cuts off text that runs past the right edge. Anything after a NUL character
+    /// is dropped, since the SDK can't show it.
+    ///
+    /// The SDK draws the glyphs itself, so the driver doesn't know which pixels they cover: the
+    /// text isn't [rotated](Self::set_rotation) or [mirrored](Self::set_mirror), doesn't wait
+    /// for a [managed region](Self::set_managed_region), and isn't recorded in the [shadow
+    /// buffer](Self::enable_shadow_buffer), so anything later restored from it, like layers,
+    /// draws over the text. The text's box is still added to the [dirty
+    /// area](Self::dirty_area).
+    pub fn draw_builtin_text(&mut self, row: i32, col: i32, color: C, text: &str) {
+        if row < 0 || col < 0 {
+            return;
+        }
+        let text = text.split('\0').next().unwrap_or_default();
+        let Ok(text) = CString::new(text) else {
+            return;
+        };
+
+        let cell_width = self.display.text_width(c"0");
+        let drawable = self.drawable_area();
+        let (Some(x), Some(y)) = (
+            col.checked_mul(cell_width)
+                .and_then(|x| x.checked_add(drawable.top_left.x)),
+            row.checked_mul(LINE_HEIGHT as i32)
+                .and_then(|y| y.checked_add(drawable.top_left.y)),
+        ) else {
+            return;
+        };
+        let top_left = Point::new(x, y);
+        if !drawable.contains(top_left) {
+            return;
+        }
+
+        let width = self.display.text_width(&text).max(0) as u32;
+        let area = Rectangle::new(top_left, Size::new(width, LINE_HEIGHT));
+        self.dirty
+            .include(&area.intersection(&self.screen_bounds()));
+
+        self.set_foreground(color.to_display_storage());
+        self.display
+            .draw_text(top_left.x, self.sdk_y(top_left.y), &text);
+        #[cfg(feature = "stats")]
+        self.stats.record_draw_call();
+    }
+}
@@ -0,0 +1,247 @@
+//! Drawing into memory before anything reaches the display.
+
+use core::convert::Infallible;
+
+use embedded_graphics_core::{
+    pixelcolor::{Rgb888, raw::RawU24},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use crate::{DisplayDriver, PanelBackend, WIDTH, frame::safe_intersection};
+
+/// An in-memory image that can be drawn to like the display and then copied onto it.
+///
+/// Pixels are stored in the display's native format, so copying a canvas to the display with
+/// [`DisplayDriver::blit_canvas_window`] needs no conversion. A canvas can be larger than the
+/// screen, which makes it a convenient source for scrolling views.
+///
+/// A canvas the size of the display also works as an offscreen framebuffer: draw each frame into
+/// it, then [`present`](DisplayDriver::present) it, for flicker-free updates without the SDK's
+/// double buffered mode. Since drawing to a canvas doesn't need the brain, drawing code can be
+/// checked pixel by pixel on a host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffscreenCanvas {
+    size: Size,
+    pixels: Vec<u32>,
+}
+
+impl OffscreenCanvas {
+    /// Creates a new canvas filled with black.
+    #[must_use]
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            pixels: vec![0; size.width as usize * size.height as usize],
+        }
+    }
+
+    /// Returns the color at `point`, or `None` if it is outside the canvas.
+    #[must_use]
+    pub fn pixel(&self, point: Point) -> Option<Rgb888> {
+        self.index(point)
+            .map(|index| Rgb888::from(RawU24::new(self.pixels[index])))
+    }
+
+    /// Returns the canvas' pixels in the display's native format, in row-major order.
+    #[must_use]
+    pub fn pixels(&self) -> &[u32] {
+        &self.pixels
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        self.bounding_box()
+            .contains(point)
+            .then(|| point.y as usize * self.size.width as usize + point.x as usize)
+    }
+}
+
+impl OriginDimensions for OffscreenCanvas {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for OffscreenCanvas {
+    type Color = Rgb888;
+
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(pos, color) in pixels {
+            if let Some(index) = self.index(pos) {
+                self.pixels[index] = color.into_storage();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = safe_intersection(area, &self.bounding_box());
+        let width = self.size.width as usize;
+        for y in area.rows() {
+            let start = y as usize * width + area.top_left.x as usize;
+            self.pixels[start..][..area.size.width as usize].fill(color.into_storage());
+        }
+
+        Ok(())
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Copies a display-sized window of `canvas`, starting at `src_origin`, onto the display.
+    ///
+    /// The window is moved as needed to keep it inside the canvas, so scrolling past the canvas'
+    /// edges stops at the edge instead of showing stale pixels. A canvas smaller than the display
+    /// is copied whole. Returns the origin of the window that was actually copied, which callers
+    /// can store as their new scroll position.
+    pub fn blit_canvas_window(&mut self, canvas: &OffscreenCanvas, src_origin: Point) -> Point {
+        let window = self.size().component_min(canvas.size);
+        let max_origin = canvas.size - window;
+        let origin = src_origin
+            .component_max(Point::zero())
+            .component_min(Point::new(
+                max_origin.width as i32,
+                max_origin.height as i32,
+            ));
+
+        let canvas_width = canvas.size.width as usize;
+        let (left, top) = (origin.x as usize, origin.y as usize);
+        let colors = (0..window.height as usize).flat_map(|y| {
+            let start = (top + y) * canvas_width + left;
+            canvas.pixels[start..][..window.width as usize]
+                .iter()
+                .copied()
+        });
+        self.fill_contiguous_storage(&Rectangle::new(Point::zero(), window), colors);
+
+        origin
+    }
+
+    /// Copies the whole of `canvas` onto the display, with its top left corner at `(0, 0)`.
+    ///
+    /// The part of the canvas that is on screen is copied with a single SDK call, straight from
+    /// the canvas' memory. That isn't possible while the display is [rotated](Self::set_rotation)
+    /// or [mirrored](Self::set_mirror), or while colors are changed on their way to the panel by
+    /// the [brightness](Self::set_brightness), [gamma](Self::set_gamma) or [storage
+    /// order](Self::set_storage_byte_order), so then the canvas is copied a row at a time instead. A
+    /// [managed region](Self::set_managed_region) also splits the copy up.
+    pub fn present(&mut self, canvas: &OffscreenCanvas) {
+        let area = Rectangle::new(Point::zero(), canvas.size);
+        if self.is_reoriented() || self.has_color_transform() {
+            self.fill_contiguous_storage(&area, canvas.pixels.iter().copied());
+            return;
+        }
+
+        let visible = self
+            .clip_to_screen(&area)
+            .intersection(&self.screen_bounds());
+        if visible.is_zero_sized() {
+            return;
+        }
+
+        let stride = canvas.size.width as usize;
+        let origin = self.drawable_area().top_left;
+        let index = |point: Point| {
+            let point = point - origin;
+            point.y as usize * stride + point.x as usize
+        };
+
+        if let Some(shadow) = &mut self.shadow {
+            let width = visible.size.width as usize;
+            for y in visible.rows() {
+                let row = Point::new(visible.top_left.x, y);
+                let start = y as usize * WIDTH as usize + row.x as usize;
+                shadow[start..][..width].copy_from_slice(&canvas.pixels[index(row)..][..width]);
+            }
+        }
+        self.dirty.include(&visible);
+
+        for part in self.unmanaged_parts(&visible) {
+            let bottom_right = part.top_left + part.size - Point::new(1, 1);
+            self.display.copy_rect(
+                part.top_left.x,
+                self.sdk_y(part.top_left.y),
+                bottom_right.x,
+                self.sdk_y(bottom_right.y),
+                &canvas.pixels[index(part.top_left)..],
+                stride as i32,
+            );
+            #[cfg(feature = "stats")]
+            self.stats
+                .record_rect_copy(part.size.width * part.size.height);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockPanel;
+
+    /// The color a pixel of [`big_canvas`] gets, which encodes its position.
+    fn color_at(x: i32, y: i32) -> u32 {
+        ((x as u32) << 12) | y as u32
+    }
+
+    /// A canvas twice the size of the screen in each direction.
+    fn big_canvas() -> OffscreenCanvas {
+        let mut canvas = OffscreenCanvas::new(Size::new(960, 480));
+        let pixels = canvas
+            .bounding_box()
+            .points()
+            .map(|point| Pixel(point, Rgb888::from(RawU24::new(color_at(point.x, point.y)))));
+        let Ok(()) = canvas.draw_iter(pixels);
+        canvas
+    }
+
+    #[test]
+    fn blit_canvas_window_shows_the_offset_window() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        let origin = display.blit_canvas_window(&big_canvas(), Point::new(100, 50));
+
+        assert_eq!(origin, Point::new(100, 50));
+        let panel = display.backend();
+        assert_eq!(panel.pixel(0, 0), Some(color_at(100, 50)));
+        assert_eq!(panel.pixel(479, 0), Some(color_at(579, 50)));
+        assert_eq!(panel.pixel(479, 239), Some(color_at(579, 289)));
+        assert_eq!(panel.pixel(123, 45), Some(color_at(223, 95)));
+    }
+
+    #[test]
+    fn blit_canvas_window_is_clamped_to_the_canvas() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let canvas = big_canvas();
+
+        assert_eq!(
+            display.blit_canvas_window(&canvas, Point::new(900, 1000)),
+            Point::new(480, 240)
+        );
+        assert_eq!(display.backend().pixel(479, 239), Some(color_at(959, 479)));
+
+        assert_eq!(
+            display.blit_canvas_window(&canvas, Point::new(-5, -5)),
+            Point::zero()
+        );
+        assert_eq!(display.backend().pixel(0, 0), Some(color_at(0, 0)));
+    }
+
+    #[test]
+    fn small_canvases_are_copied_whole() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let mut canvas = OffscreenCanvas::new(Size::new(10, 5));
+        let Ok(()) = canvas.clear(Rgb888::RED);
+
+        assert_eq!(
+            display.blit_canvas_window(&canvas, Point::new(3, 3)),
+            Point::zero()
+        );
+        assert_eq!(display.backend().pixel(9, 4), Some(0xFF0000));
+        assert_eq!(display.backend().pixel(10, 4), Some(0));
+    }
+}
@@ -0,0 +1,320 @@
+//! Drawing methods that report bad requests instead of clipping them.
+//!
+//! The [`DrawTarget`] methods on [`DisplayDriver`] follow embedded-graphics' conventions: anything
+//! outside of the display is silently clipped, and an area with nothing visible is a no-op. The
+//! `try_` methods here draw exactly the same pixels, but return a [`DisplayError`] when a request
+//! is malformed or draws nothing at all, and otherwise return the part of the area that was
+//! actually drawn.
+
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use crate::{
+    DisplayDriver, DisplayError, PanelBackend, ToDisplayStorage, blit::image_size,
+    frame::safe_intersection,
+};
+
+impl<C: ToDisplayStorage, B: PanelBackend> DisplayDriver<C, B> {
+    /// Fills `area` with a solid color, like [`DrawTarget::fill_solid`].
+    ///
+    /// Returns the part of `area` that was drawn.
+    ///
+    /// # Errors
+    ///
+    /// - [`DisplayError::InvalidArea`] if `area` is zero sized or its corners overflow.
+    /// - [`DisplayError::AreaOutOfBounds`] if no part of `area` is on the display.
+    pub fn try_fill_solid(
+        &mut self,
+        area: &Rectangle,
+        color: C,
+    ) -> Result<Rectangle, DisplayError> {
+        let visible = self.validate_area(area)?;
+        self.fill_solid_storage(area, color.to_display_storage());
+
+        Ok(visible)
+    }
+
+    /// Fills `area` with colors from an iterator, like [`DrawTarget::fill_contiguous`].
+    ///
+    /// Returns the part of `area` that was drawn.
+    ///
+    /// # Errors
+    ///
+    /// - [`DisplayError::InvalidArea`] if `area` is zero sized or its corners overflow.
+    /// - [`DisplayError::AreaOutOfBounds`] if no part of `area` is on the display.
+    /// - [`DisplayError::ColorBufferTooSmall`] if `colors` runs out before the visible part of
+    ///   `area` is covered, in which case the pixels it had colors for are drawn and the rest of
+    ///   `area` is left as it was.
+    pub fn try_fill_contiguous<I>(
+        &mut self,
+        area: &Rectangle,
+        colors: I,
+    ) -> Result<Rectangle, DisplayError>
+    where
+        I: IntoIterator<Item = C>,
+    {
+        let visible = self.validate_area(area)?;
+        if !self.fill_contiguous_storage(area, colors.into_iter().map(C::to_display_storage)) {
+            return Err(DisplayError::ColorBufferTooSmall);
+        }
+
+        Ok(visible)
+    }
+
+    /// Draws an image, like [`blit`](Self::blit).
+    ///
+    /// Returns the part of the image's area that was drawn.
+    ///
+    /// # Errors
+    ///
+    /// - [`DisplayError::InvalidImage`] if `src_width` is zero or `src` has no full rows.
+    /// - [`DisplayError::InvalidArea`] if the far corner of the image overflows.
+    /// - [`DisplayError::AreaOutOfBounds`] if no part of the image is on the display.
+    pub fn try_blit(
+        &mut self,
+        top_left: Point,
+        src: &[C],
+        src_width: u32,
+    ) -> Result<Rectangle, DisplayError> {
+        let size = validate_image(src, src_width)?;
+        let visible = self.validate_area(&Rectangle::new(top_left, size))?;
+        self.blit(top_left, src, src_width);
+
+        Ok(visible)
+    }
+
+    /// Draws an image scaled to fill `area`, like [`blit_scaled`](Self::blit_scaled).
+    ///
+    /// Returns the part of `area` that was drawn.
+    ///
+    /// # Errors
+    ///
+    /// - [`DisplayError::InvalidImage`] if `src_width` is zero or `src` has no full rows.
+    /// - [`DisplayError::InvalidArea`] if `area` is zero sized or its corners overflow.
+    /// - [`DisplayError::AreaOutOfBounds`] if no part of `area` is on the display.
+    pub fn try_blit_scaled(
+        &mut self,
+        area: &Rectangle,
+        src: &[C],
+        src_width: u32,
+    ) -> Result<Rectangle, DisplayError> {
+        validate_image(src, src_width)?;
+        let visible = self.validate_area(area)?;
+        self.blit_scaled(area, src, src_width);
+
+        Ok(visible)
+    }
+}
+
+impl<C, B> DisplayDriver<C, B> {
+    /// Returns the visible part of `area`, in embedded-graphics coordinates.
+    fn validate_area(&self, area: &Rectangle) -> Result<Rectangle, DisplayError> {
+        let fits = |start: i32, length: u32| {
+            length > 0
+                && length <= i32::MAX as u32
+                && start.checked_add(length as i32 - 1).is_some()
+        };
+        if !fits(area.top_left.x, area.size.width) || !fits(area.top_left.y, area.size.height) {
+            return Err(DisplayError::InvalidArea);
+        }
+
+        let visible = safe_intersection(area, &self.bounding_box());
+        if visible.is_zero_sized() {
+            return Err(DisplayError::AreaOutOfBounds);
+        }
+
+        Ok(visible)
+    }
+}
+
+/// Returns the size of an image, or an error if it has no full rows.
+fn validate_image<C>(src: &[C], src_width: u32) -> Result<Size, DisplayError> {
+    let size = image_size(src, src_width);
+    if size.height == 0 {
+        return Err(DisplayError::InvalidImage);
+    }
+
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::{
+        Pixel,
+        pixelcolor::{Rgb888, raw::RawU24},
+    };
+
+    use super::*;
+    use crate::{MockPanel, Rotation};
+
+    /// A distinct color for each of the first 2^24 pixels.
+    fn numbered() -> impl Iterator<Item = Rgb888> {
+        (1..).map(|i| Rgb888::from(RawU24::new(i)))
+    }
+
+    #[test]
+    fn short_iterators_draw_the_pixels_they_cover_under_every_rotation() {
+        let area = Rectangle::new(Point::new(-3, 4), Size::new(10, 3));
+        for rotation in [
+            Rotation::Deg0,
+            Rotation::Deg90,
+            Rotation::Deg180,
+            Rotation::Deg270,
+        ] {
+            let mut filled = DisplayDriver::new(MockPanel::new());
+            filled.set_rotation(rotation);
+            let result = filled.try_fill_contiguous(&area, numbered().take(13));
+
+            let mut expected = DisplayDriver::new(MockPanel::new());
+            expected.set_rotation(rotation);
+            expected
+                .draw_iter(
+                    area.points()
+                        .zip(numbered())
+                        .take(13)
+                        .map(|(p, c)| Pixel(p, c)),
+                )
+                .unwrap();
+
+            assert_eq!(result, Err(DisplayError::ColorBufferTooSmall));
+            assert!(
+                filled.backend().framebuffer() == expected.backend().framebuffer(),
+                "short fill differs under {rotation:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn try_fill_contiguous_returns_the_visible_part() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let area = Rectangle::new(Point::new(-5, -5), Size::new(10, 10));
+
+        let result = display.try_fill_contiguous(&area, core::iter::repeat_n(Rgb888::RED, 100));
+
+        assert_eq!(result, Ok(Rectangle::new(Point::zero(), Size::new(5, 5))));
+        assert_eq!(display.backend().pixel(4, 4), Some(0xFF0000));
+        assert_eq!(display.backend().pixel(5, 5), Some(0));
+    }
+
+    #[test]
+    fn try_fill_solid_reports_bad_areas() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        let empty = Rectangle::new(Point::zero(), Size::new(0, 5));
+        let overflowing = Rectangle::new(Point::new(i32::MAX, 0), Size::new(2, 1));
+        let off_screen = Rectangle::new(Point::new(-10, 0), Size::new(5, 5));
+
+        assert_eq!(
+            display.try_fill_solid(&empty, Rgb888::RED),
+            Err(DisplayError::InvalidArea)
+        );
+        assert_eq!(
+            display.try_fill_solid(&overflowing, Rgb888::RED),
+            Err(DisplayError::InvalidArea)
+        );
+        assert_eq!(
+            display.try_fill_solid(&off_screen, Rgb888::RED),
+            Err(DisplayError::AreaOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn try_fill_solid_returns_the_visible_part() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let area = Rectangle::new(Point::new(470, 230), Size::new(20, 20));
+
+        let result = display.try_fill_solid(&area, Rgb888::RED);
+
+        assert_eq!(
+            result,
+            Ok(Rectangle::new(Point::new(470, 230), Size::new(10, 10)))
+        );
+        assert_eq!(display.backend().pixel(479, 239), Some(0xFF0000));
+    }
+
+    #[test]
+    fn try_fill_contiguous_reports_bad_areas() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let colors = || core::iter::repeat_n(Rgb888::RED, 100);
+
+        assert_eq!(
+            display.try_fill_contiguous(&Rectangle::zero(), colors()),
+            Err(DisplayError::InvalidArea)
+        );
+        assert_eq!(
+            display.try_fill_contiguous(
+                &Rectangle::new(Point::new(480, 0), Size::new(5, 5)),
+                colors()
+            ),
+            Err(DisplayError::AreaOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn try_blit_draws_valid_images() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let image = [Rgb888::RED, Rgb888::GREEN, Rgb888::BLUE, Rgb888::WHITE];
+
+        let result = display.try_blit(Point::new(-1, 0), &image, 2);
+
+        assert_eq!(result, Ok(Rectangle::new(Point::zero(), Size::new(1, 2))));
+        assert_eq!(display.backend().pixel(0, 0), Some(0x00FF00));
+        assert_eq!(display.backend().pixel(0, 1), Some(0xFFFFFF));
+    }
+
+    #[test]
+    fn try_blit_reports_bad_images_and_areas() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let image = [Rgb888::RED; 4];
+
+        assert_eq!(
+            display.try_blit(Point::zero(), &image, 0),
+            Err(DisplayError::InvalidImage)
+        );
+        assert_eq!(
+            display.try_blit(Point::new(i32::MAX, 0), &image, 2),
+            Err(DisplayError::InvalidArea)
+        );
+        assert_eq!(
+            display.try_blit(Point::new(0, -2), &image, 2),
+            Err(DisplayError::AreaOutOfBounds)
+        );
+        assert!(display.backend().framebuffer().iter().all(|&c| c == 0));
+    }
+
+    #[test]
+    fn try_blit_scaled_draws_valid_images() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let area = Rectangle::new(Point::zero(), Size::new(4, 4));
+
+        let result = display.try_blit_scaled(&area, &[Rgb888::RED, Rgb888::BLUE], 2);
+
+        assert_eq!(result, Ok(area));
+        assert_eq!(display.backend().pixel(1, 3), Some(0xFF0000));
+        assert_eq!(display.backend().pixel(2, 3), Some(0x0000FF));
+    }
+
+    #[test]
+    fn try_blit_scaled_reports_bad_images_and_areas() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let image = [Rgb888::RED; 4];
+        let area = Rectangle::new(Point::zero(), Size::new(4, 4));
+
+        assert_eq!(
+            display.try_blit_scaled(&area, &image, 5),
+            Err(DisplayError::InvalidImage)
+        );
+        assert_eq!(
+            display.try_blit_scaled(&Rectangle::new(Point::zero(), Size::new(4, 0)), &image, 2),
+            Err(DisplayError::InvalidArea)
+        );
+        assert_eq!(
+            display.try_blit_scaled(
+                &Rectangle::new(Point::new(0, 240), Size::new(4, 4)),
+                &image,
+                2
+            ),
+            Err(DisplayError::AreaOutOfBounds)
+        );
+        assert!(display.backend().framebuffer().iter().all(|&c| c == 0));
+    }
+}
@@ -0,0 +1,295 @@
+//! Drawing clipped to a circle.
+
+use embedded_graphics::primitives::{Circle, ContainsPoint};
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use crate::{
+    DisplayDriver, DisplayError, PanelBackend, ToDisplayStorage, frame::safe_intersection, skip,
+};
+
+/// A draw target that only draws the pixels inside a circle.
+///
+/// Round gauges and widgets can draw rectangular content, like a background image or a scale
+/// that runs off the edge, and have it show up only inside their circular bezel. Coordinates are
+/// the same as the display's, and a pixel is inside if [`Circle::contains`] says so, so the
+/// clipped area exactly matches a filled circle drawn with the same [`Circle`]. Fills are clipped
+/// row by row, so each row of a fill is still sent to the display as a single SDK call.
+///
+/// Created with [`DisplayDriver::clip_circle`].
+pub struct ClippedTarget<'a, C, B: PanelBackend> {
+    driver: &'a mut DisplayDriver<C, B>,
+    circle: Circle,
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Returns a draw target that discards everything outside of `circle`.
+    ///
+    /// See [`ClippedTarget`].
+    pub fn clip_circle(&mut self, circle: Circle) -> ClippedTarget<'_, C, B> {
+        ClippedTarget {
+            driver: self,
+            circle,
+        }
+    }
+}
+
+impl<C, B: PanelBackend> ClippedTarget<'_, C, B> {
+    /// Returns the circle that drawing is clipped to.
+    #[must_use]
+    pub const fn circle(&self) -> Circle {
+        self.circle
+    }
+
+    /// Returns the part of `area` that is inside both the circle's bounding box and the display.
+    fn visible(&self, area: &Rectangle) -> Rectangle {
+        let visible = safe_intersection(area, &self.driver.bounding_box());
+        safe_intersection(&visible, &self.circle.bounding_box())
+    }
+
+    /// Returns the first and last column of row `y` that are inside both the circle and `area`.
+    fn row_span(&self, area: &Rectangle, y: i32) -> Option<(i32, i32)> {
+        let (left, right) = circle_row(&self.circle, y)?;
+        let left = left.max(area.top_left.x);
+        let area_right = i64::from(area.top_left.x) + i64::from(area.size.width) - 1;
+        let right = i64::from(right).min(area_right) as i32;
+        (left <= right).then_some((left, right))
+    }
+}
+
+impl<C, B: PanelBackend> OriginDimensions for ClippedTarget<'_, C, B> {
+    fn size(&self) -> Size {
+        self.driver.size()
+    }
+}
+
+impl<C: ToDisplayStorage, B: PanelBackend> DrawTarget for ClippedTarget<'_, C, B> {
+    type Color = C;
+
+    type Error = DisplayError;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if self.circle.contains(point)
+                && let Some(point) = self.driver.to_screen(point)
+            {
+                self.driver.write_pixel(point, color.to_display_storage());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let visible = self.visible(area);
+        if visible.is_zero_sized() {
+            return Ok(());
+        }
+
+        // Colors for the rows below the last visible one can't be seen, so they aren't read.
+        let width = area.size.width as usize;
+        let last_row = visible.top_left.y + visible.size.height as i32 - 1;
+        let mut colors = colors.into_iter().map(C::to_display_storage);
+        let above = visible.top_left.y.abs_diff(area.top_left.y) as usize;
+        if !skip(&mut colors, above.saturating_mul(width)) {
+            return Err(DisplayError::ColorBufferTooSmall);
+        }
+
+        for y in visible.rows() {
+            let mut after = width;
+            if let Some((left, right)) = self.row_span(area, y) {
+                let before = left.abs_diff(area.top_left.x) as usize;
+                let len = (right - left + 1) as usize;
+                if !skip(&mut colors, before) {
+                    return Err(DisplayError::ColorBufferTooSmall);
+                }
+
+                let span = Rectangle::new(Point::new(left, y), Size::new(len as u32, 1));
+                let mut span_colors = colors.by_ref().take(len);
+                if !self.driver.fill_contiguous_storage(&span, &mut span_colors) {
+                    return Err(DisplayError::ColorBufferTooSmall);
+                }
+                // The part of the span that is off screen may not have been read.
+                span_colors.for_each(drop);
+                after = width - before - len;
+            }
+
+            if y < last_row && !skip(&mut colors, after) {
+                return Err(DisplayError::ColorBufferTooSmall);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let color = color.to_display_storage();
+        for y in self.visible(area).rows() {
+            if let Some((left, right)) = self.row_span(area, y) {
+                let span = Rectangle::with_corners(Point::new(left, y), Point::new(right, y));
+                self.driver.fill_solid_storage(&span, color);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the first and last column of row `y` that are inside `circle`, or `None` if the row
+/// misses it.
+fn circle_row(circle: &Circle, y: i32) -> Option<(i32, i32)> {
+    let bounds = circle.bounding_box();
+    let center = Point::new(circle.center().x, y);
+    if !circle.contains(center) {
+        return None;
+    }
+
+    // Along a row, the points inside the circle are a single run around its center column, so
+    // each end can be found with a binary search.
+    let inside = |x: i32| circle.contains(Point::new(x, y));
+    let (mut outside, mut inner) = (bounds.top_left.x - 1, center.x);
+    while inner - outside > 1 {
+        let mid = outside + (inner - outside) / 2;
+        if inside(mid) {
+            inner = mid
+        } else {
+            outside = mid
+        }
+    }
+    let left = inner;
+
+    let (mut inner, mut outside) = (center.x, bounds.top_left.x + bounds.size.width as i32);
+    while outside - inner > 1 {
+        let mid = inner + (outside - inner) / 2;
+        if inside(mid) {
+            inner = mid
+        } else {
+            outside = mid
+        }
+    }
+
+    Some((left, inner))
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::primitives::{PrimitiveStyle, StyledDrawable};
+    use embedded_graphics_core::pixelcolor::Rgb888;
+
+    use super::*;
+    use crate::MockPanel;
+
+    /// A display with `circle` filled in red, drawn without any clipping.
+    fn filled_circle(circle: &Circle) -> DisplayDriver<Rgb888, MockPanel> {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        circle
+            .draw_styled(&PrimitiveStyle::with_fill(Rgb888::RED), &mut display)
+            .unwrap();
+        display
+    }
+
+    #[test]
+    fn circle_row_spans_exactly_the_points_inside() {
+        for diameter in 1..=24 {
+            let circle = Circle::new(Point::new(-3, 7), diameter);
+            let bounds = circle.bounding_box();
+            for y in bounds.top_left.y - 1..=bounds.top_left.y + diameter as i32 {
+                let inside: Vec<i32> = (bounds.top_left.x - 1
+                    ..=bounds.top_left.x + diameter as i32)
+                    .filter(|&x| circle.contains(Point::new(x, y)))
+                    .collect();
+                let expected = inside.first().map(|&left| (left, *inside.last().unwrap()));
+
+                assert_eq!(
+                    circle_row(&circle, y),
+                    expected,
+                    "diameter {diameter}, row {y}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn pixels_are_only_drawn_inside_the_edge() {
+        let circle = Circle::new(Point::new(10, 10), 11);
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        let pixels = [(10, 15), (9, 15), (15, 10), (15, 9), (20, 15), (21, 15)]
+            .map(|(x, y)| Pixel(Point::new(x, y), Rgb888::RED));
+        display.clip_circle(circle).draw_iter(pixels).unwrap();
+
+        // Just inside the left, top and right edges is drawn, and just outside isn't.
+        assert_eq!(display.backend().pixel(10, 15), Some(0xFF0000));
+        assert_eq!(display.backend().pixel(9, 15), Some(0));
+        assert_eq!(display.backend().pixel(15, 10), Some(0xFF0000));
+        assert_eq!(display.backend().pixel(15, 9), Some(0));
+        assert_eq!(display.backend().pixel(20, 15), Some(0xFF0000));
+        assert_eq!(display.backend().pixel(21, 15), Some(0));
+    }
+
+    #[test]
+    fn fill_solid_is_cut_to_the_circle() {
+        let circle = Circle::new(Point::new(30, 20), 17);
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        let area = display.bounding_box();
+        display
+            .clip_circle(circle)
+            .fill_solid(&area, Rgb888::RED)
+            .unwrap();
+
+        let expected = filled_circle(&circle);
+        assert!(
+            display.backend().framebuffer() == expected.backend().framebuffer(),
+            "the fill should cover exactly the circle"
+        );
+        // Each row of the circle is a single fill.
+        assert_eq!(display.backend().fill_rects().len(), 17);
+    }
+
+    #[test]
+    fn fill_contiguous_is_cut_to_the_circle() {
+        let circle = Circle::new(Point::new(-4, -6), 20);
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        let area = Rectangle::new(Point::new(-10, -10), Size::new(40, 40));
+        display
+            .clip_circle(circle)
+            .fill_contiguous(&area, core::iter::repeat_n(Rgb888::RED, 40 * 40))
+            .unwrap();
+
+        let expected = filled_circle(&circle);
+        assert!(
+            display.backend().framebuffer() == expected.backend().framebuffer(),
+            "the fill should cover exactly the on-screen part of the circle"
+        );
+    }
+
+    #[test]
+    fn a_zero_sized_circle_clips_everything() {
+        let circle = Circle::new(Point::new(10, 10), 0);
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        let area = display.bounding_box();
+        let mut clipped = display.clip_circle(circle);
+        clipped.fill_solid(&area, Rgb888::RED).unwrap();
+        clipped
+            .draw_iter([Pixel(Point::new(10, 10), Rgb888::RED)])
+            .unwrap();
+
+        assert_eq!(circle_row(&circle, 10), None);
+        assert!(
+            display
+                .backend()
+                .framebuffer()
+                .iter()
+                .all(|&color| color == 0)
+        );
+        assert!(display.backend().fill_rects().is_empty());
+    }
+}
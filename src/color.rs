@@ -0,0 +1,182 @@
+//! Conversion from embedded-graphics colors into the display's native format.
+
+use embedded_graphics_core::{
+    pixelcolor::{
+        Bgr555, Bgr565, Bgr666, Bgr888, BinaryColor, Gray2, Gray4, Gray8, Rgb555, Rgb565, Rgb666,
+        Rgb888,
+    },
+    prelude::*,
+};
+
+/// A color that a [`DisplayDriver`](crate::DisplayDriver) can draw with.
+///
+/// The VEX SDK takes colors as a `u32` in `0x00RRGGBB` format. This trait is implemented for all
+/// of the RGB, grayscale and binary color types in `embedded-graphics`, so a driver can be created
+/// for whichever one your assets are authored in and each color is converted once, right before
+/// it is handed to the SDK.
+///
+/// # Custom colors
+///
+/// To draw with a color type of your own, implement this trait for it. A type that already
+/// converts into [`Rgb888`] only needs `Rgb888::from(self).into_storage()`, which is how the
+/// `embedded-graphics` types other than [`Rgb888`] itself are implemented. Otherwise, compute the
+/// `0x00RRGGBB` value directly:
+///
+/// ```
+/// use embedded_graphics_core::pixelcolor::{PixelColor, raw::RawU8};
+/// use vexide_embedded_graphics::ToDisplayStorage;
+///
+/// /// One of eight fixed colors.
+/// #[derive(Clone, Copy, PartialEq)]
+/// struct Palette8(u8);
+///
+/// impl PixelColor for Palette8 {
+///     type Raw = RawU8;
+/// }
+///
+/// impl ToDisplayStorage for Palette8 {
+///     fn to_display_storage(self) -> u32 {
+///         const COLORS: [u32; 8] = [
+///             0x000000, 0xFF0000, 0x00FF00, 0x0000FF, 0xFFFF00, 0x00FFFF, 0xFF00FF, 0xFFFFFF,
+///         ];
+///         COLORS[usize::from(self.0 & 0b111)]
+///     }
+/// }
+/// ```
+pub trait ToDisplayStorage: PixelColor {
+    /// Converts the color into the display's native `0x00RRGGBB` format.
+    fn to_display_storage(self) -> u32;
+}
+
+impl ToDisplayStorage for Rgb888 {
+    fn to_display_storage(self) -> u32 {
+        self.into_storage()
+    }
+}
+
+/// Converts `color` into the `0x00RRGGBB` value the VEX SDK takes, with red in bits 16-23, green
+/// in bits 8-15 and blue in bits 0-7.
+///
+/// This is the conversion the driver applies to every color it draws, before any output
+/// adjustments like the [storage order](crate::DisplayDriver::set_storage_byte_order), and is
+/// the same as calling [`ToDisplayStorage::to_display_storage`].
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics_core::pixelcolor::Rgb888;
+/// use vexide_embedded_graphics::to_storage;
+///
+/// assert_eq!(to_storage(Rgb888::new(0x12, 0x34, 0x56)), 0x123456);
+/// ```
+#[must_use]
+pub fn to_storage<C: ToDisplayStorage>(color: C) -> u32 {
+    color.to_display_storage()
+}
+
+/// Implements [`ToDisplayStorage`] by converting through [`Rgb888`].
+macro_rules! impl_via_rgb888 {
+    ($($color:ty),+) => {
+        $(impl ToDisplayStorage for $color {
+            fn to_display_storage(self) -> u32 {
+                Rgb888::from(self).into_storage()
+            }
+        })+
+    };
+}
+
+impl_via_rgb888! {
+    Rgb555, Bgr555, Rgb565, Bgr565, Rgb666, Bgr666, Bgr888, Gray2, Gray4, Gray8, BinaryColor
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::{pixelcolor::raw::RawU24, primitives::Rectangle};
+
+    use super::*;
+    use crate::{DisplayDriver, MockPanel};
+
+    /// Fills a pixel with each of `colors` in turn and returns what the panel showed.
+    fn rendered<C: ToDisplayStorage>(colors: [C; 5]) -> [u32; 5] {
+        let mut display = DisplayDriver::<C, _>::with_color_type(MockPanel::new());
+        let pixel = Rectangle::new(Point::zero(), Size::new(1, 1));
+        colors.map(|color| {
+            display.fill_solid(&pixel, color).unwrap();
+            display.backend().pixel(0, 0).unwrap()
+        })
+    }
+
+    const PRIMARIES: [u32; 5] = [0xFF0000, 0x00FF00, 0x0000FF, 0xFFFFFF, 0x000000];
+
+    macro_rules! assert_primaries {
+        ($($color:ty),+) => {
+            $(assert_eq!(
+                rendered([<$color>::RED, <$color>::GREEN, <$color>::BLUE, <$color>::WHITE, <$color>::BLACK]),
+                PRIMARIES,
+                "{}",
+                stringify!($color),
+            );)+
+        };
+    }
+
+    #[test]
+    fn to_storage_puts_red_in_the_high_byte() {
+        assert_eq!(to_storage(Rgb888::new(0x12, 0x34, 0x56)), 0x123456);
+        assert_eq!(to_storage(Bgr888::new(0x12, 0x34, 0x56)), 0x123456);
+    }
+
+    #[test]
+    fn to_storage_expands_rgb565_channels() {
+        assert_eq!(to_storage(Rgb565::new(31, 0, 0)), 0xFF0000);
+        assert_eq!(to_storage(Rgb565::new(0, 63, 0)), 0x00FF00);
+        assert_eq!(to_storage(Rgb565::new(0, 0, 31)), 0x0000FF);
+        assert_eq!(to_storage(Rgb565::new(16, 32, 16)), 0x848284);
+    }
+
+    #[test]
+    fn to_storage_copies_gray_to_every_channel() {
+        assert_eq!(to_storage(Gray8::new(0x80)), 0x808080);
+        assert_eq!(to_storage(Gray8::new(0x01)), 0x010101);
+    }
+
+    #[test]
+    fn to_storage_matches_to_display_storage() {
+        for raw in [0, 0x123456, 0xFFFFFF] {
+            let color = Rgb888::from(RawU24::new(raw));
+            assert_eq!(to_storage(color), color.to_display_storage());
+            assert_eq!(to_storage(color), raw);
+        }
+    }
+
+    #[test]
+    fn rgb_formats_render_their_primary_colors() {
+        assert_primaries!(
+            Rgb555, Bgr555, Rgb565, Bgr565, Rgb666, Bgr666, Rgb888, Bgr888
+        );
+    }
+
+    #[test]
+    fn gray_and_binary_formats_render_black_and_white() {
+        const BLACK_AND_WHITE: [u32; 5] = [0xFFFFFF, 0x000000, 0xFFFFFF, 0x000000, 0xFFFFFF];
+        fn alternating<C: Copy>(white: C, black: C) -> [C; 5] {
+            [white, black, white, black, white]
+        }
+
+        assert_eq!(
+            rendered(alternating(Gray2::WHITE, Gray2::BLACK)),
+            BLACK_AND_WHITE
+        );
+        assert_eq!(
+            rendered(alternating(Gray4::WHITE, Gray4::BLACK)),
+            BLACK_AND_WHITE
+        );
+        assert_eq!(
+            rendered(alternating(Gray8::WHITE, Gray8::BLACK)),
+            BLACK_AND_WHITE
+        );
+        assert_eq!(
+            rendered(alternating(BinaryColor::On, BinaryColor::Off)),
+            BLACK_AND_WHITE
+        );
+    }
+}
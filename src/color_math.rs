@@ -0,0 +1,89 @@
+//! Channel arithmetic for [`Rgb888`] colors.
+//!
+//! Every function here works on each channel independently and saturates instead of wrapping, so
+//! lightening a color that is already bright clamps it at white rather than wrapping it around to
+//! something dark.
+
+use embedded_graphics_core::pixelcolor::{Rgb888, RgbColor};
+
+/// Applies `op` to each pair of channels in `a` and `b`.
+fn zip_channels(a: Rgb888, b: Rgb888, op: impl Fn(u8, u8) -> u8) -> Rgb888 {
+    Rgb888::new(op(a.r(), b.r()), op(a.g(), b.g()), op(a.b(), b.b()))
+}
+
+/// Adds `amount` to each channel, clamping at 255.
+#[must_use]
+pub fn lighten(color: Rgb888, amount: u8) -> Rgb888 {
+    add(color, Rgb888::new(amount, amount, amount))
+}
+
+/// Subtracts `amount` from each channel, clamping at 0.
+#[must_use]
+pub fn darken(color: Rgb888, amount: u8) -> Rgb888 {
+    subtract(color, Rgb888::new(amount, amount, amount))
+}
+
+/// Adds the channels of two colors, clamping each at 255.
+#[must_use]
+pub fn add(a: Rgb888, b: Rgb888) -> Rgb888 {
+    zip_channels(a, b, u8::saturating_add)
+}
+
+/// Subtracts the channels of `b` from `a`, clamping each at 0.
+#[must_use]
+pub fn subtract(a: Rgb888, b: Rgb888) -> Rgb888 {
+    zip_channels(a, b, u8::saturating_sub)
+}
+
+/// Mixes two colors, from all `a` when `amount` is 0 to all `b` when it is 255.
+///
+/// Each channel is rounded to the nearest value, so the result never overshoots either input.
+#[must_use]
+pub fn mix(a: Rgb888, b: Rgb888, amount: u8) -> Rgb888 {
+    let amount = u16::from(amount);
+    zip_channels(a, b, |a, b| {
+        ((u16::from(a) * (255 - amount) + u16::from(b) * amount + 127) / 255) as u8
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lighten_saturates_at_white() {
+        assert_eq!(
+            lighten(Rgb888::new(250, 128, 0), 10),
+            Rgb888::new(255, 138, 10)
+        );
+        assert_eq!(lighten(Rgb888::WHITE, 255), Rgb888::WHITE);
+    }
+
+    #[test]
+    fn darken_saturates_at_black() {
+        assert_eq!(
+            darken(Rgb888::new(5, 128, 255), 10),
+            Rgb888::new(0, 118, 245)
+        );
+        assert_eq!(darken(Rgb888::BLACK, 255), Rgb888::BLACK);
+    }
+
+    #[test]
+    fn add_and_subtract_saturate_per_channel() {
+        let bright = Rgb888::new(200, 255, 1);
+        let other = Rgb888::new(100, 1, 1);
+
+        assert_eq!(add(bright, other), Rgb888::new(255, 255, 2));
+        assert_eq!(subtract(other, bright), Rgb888::new(0, 0, 0));
+    }
+
+    #[test]
+    fn mix_stays_between_its_inputs() {
+        let (a, b) = (Rgb888::new(0, 255, 100), Rgb888::new(255, 0, 100));
+
+        assert_eq!(mix(a, b, 0), a);
+        assert_eq!(mix(a, b, 255), b);
+        assert_eq!(mix(a, b, 128), Rgb888::new(128, 127, 100));
+        assert_eq!(mix(Rgb888::WHITE, Rgb888::WHITE, 77), Rgb888::WHITE);
+    }
+}
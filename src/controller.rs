@@ -0,0 +1,161 @@
+//! An embedded-graphics target for the controller's screen.
+
+use core::convert::Infallible;
+
+use embedded_graphics_core::{pixelcolor::BinaryColor, prelude::*};
+use vexide::controller::{Controller, ControllerError};
+
+/// Number of character cells in a line of the controller screen.
+const COLUMNS: usize = Controller::MAX_COLUMNS;
+
+/// Number of lines on the controller screen.
+const LINES: usize = Controller::MAX_LINES;
+
+/// An embedded-graphics draw target for the V5 controller's screen.
+///
+/// The controller screen can't address individual pixels, only show text in a grid of 19 by 3
+/// character cells. This driver treats each cell as one pixel: drawing [`BinaryColor::On`] puts
+/// the [on character](Self::set_glyphs) in a cell, and [`BinaryColor::Off`] blanks it. Plain text
+/// can be written into the grid too, with [`write_text`](Self::write_text).
+///
+/// Writing to the controller is slow, so drawing only changes an in-memory copy of the screen.
+/// [`render`](Self::render) then sends the lines that changed.
+pub struct ControllerDisplayDriver {
+    controller: Controller,
+    cells: [[char; COLUMNS]; LINES],
+    dirty: [bool; LINES],
+    on: char,
+    off: char,
+}
+
+impl ControllerDisplayDriver {
+    /// Create a new [`ControllerDisplayDriver`] from a [`Controller`].
+    ///
+    /// The screen starts out blank, and is cleared on the first [`render`](Self::render).
+    #[must_use]
+    pub const fn new(controller: Controller) -> Self {
+        Self {
+            controller,
+            cells: [[' '; COLUMNS]; LINES],
+            dirty: [true; LINES],
+            on: '#',
+            off: ' ',
+        }
+    }
+
+    /// Returns a reference to the controller this driver draws to.
+    #[must_use]
+    pub const fn controller(&self) -> &Controller {
+        &self.controller
+    }
+
+    /// Returns a mutable reference to the controller this driver draws to.
+    pub const fn controller_mut(&mut self) -> &mut Controller {
+        &mut self.controller
+    }
+
+    /// Sets the characters that [`BinaryColor::On`] and [`BinaryColor::Off`] pixels are shown
+    /// as, which are `'#'` and `' '` by default.
+    ///
+    /// This only affects pixels drawn afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either character is NUL, which the controller can't show.
+    pub fn set_glyphs(&mut self, on: char, off: char) {
+        assert!(
+            on != '\0' && off != '\0',
+            "the controller can't show NUL characters"
+        );
+        self.on = on;
+        self.off = off;
+    }
+
+    /// Writes `text` into the grid, starting at the cell `position` and continuing to the right.
+    ///
+    /// Text that runs past the end of the line, or starts outside of the grid, is cut off. NUL
+    /// characters are skipped, since the controller can't show them.
+    pub fn write_text(&mut self, position: Point, text: &str) {
+        let Ok(line) = usize::try_from(position.y) else {
+            return;
+        };
+        if line >= LINES {
+            return;
+        }
+
+        for (column, character) in (position.x..).zip(text.chars().filter(|&c| c != '\0')) {
+            if let Ok(column) = usize::try_from(column) {
+                self.set_cell(line, column, character);
+            }
+        }
+    }
+
+    /// Returns the character in the cell at `position`, or `None` if it is outside the grid.
+    #[must_use]
+    pub fn cell(&self, position: Point) -> Option<char> {
+        let line = usize::try_from(position.y).ok()?;
+        let column = usize::try_from(position.x).ok()?;
+        self.cells.get(line)?.get(column).copied()
+    }
+
+    /// Sends every line that changed since the last render to the controller.
+    ///
+    /// Each line is a separate write, and the controller only accepts a write every 50 ms or so
+    /// over VEXnet, so a render that changes the whole screen takes a while. Lines that failed to
+    /// send are sent again by the next render.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::Offline`] if the controller is disconnected.
+    pub async fn render(&mut self) -> Result<(), ControllerError> {
+        for line in 0..LINES {
+            if !self.dirty[line] {
+                continue;
+            }
+
+            let text: String = self.cells[line].iter().collect();
+            self.controller.set_text(text, line as u8 + 1, 1).await?;
+            self.dirty[line] = false;
+        }
+
+        Ok(())
+    }
+
+    fn set_cell(&mut self, line: usize, column: usize, character: char) {
+        if let Some(cell) = self.cells[line].get_mut(column)
+            && *cell != character
+        {
+            *cell = character;
+            self.dirty[line] = true;
+        }
+    }
+}
+
+impl OriginDimensions for ControllerDisplayDriver {
+    fn size(&self) -> Size {
+        Size::new(COLUMNS as u32, LINES as u32)
+    }
+}
+
+impl DrawTarget for ControllerDisplayDriver {
+    type Color = BinaryColor;
+
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(pos, color) in pixels {
+            let (Ok(line), Ok(column)) = (usize::try_from(pos.y), usize::try_from(pos.x)) else {
+                continue;
+            };
+            if line < LINES {
+                let character = if color.is_on() { self.on } else { self.off };
+                self.set_cell(line, column, character);
+            }
+        }
+
+        Ok(())
+    }
+}
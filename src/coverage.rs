@@ -0,0 +1,109 @@
+//! Drawing anti-aliased art as coverage of a foreground over a fixed background.
+
+use embedded_graphics_core::{
+    pixelcolor::{Gray8, GrayColor, Rgb888, raw::RawU24},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use crate::{DisplayDriver, DisplayError, PanelBackend, ToDisplayStorage, color_math};
+
+/// A draw target for anti-aliased [`Gray8`] art, which reads each pixel's luma as how much of it
+/// is covered, and draws it blended from the driver's [anti-aliasing
+/// background](DisplayDriver::set_aa_colors) at `0` to its foreground at `255`.
+///
+/// Anti-aliased font and shape renderers for embedded-graphics output [`Gray8`] coverage. Drawn
+/// as plain colors, that only looks right in white on black; this draws it in any colors. The
+/// background is fixed rather than read back from the screen, so it should match what the art is
+/// drawn over. For art over changing content, use [`DisplayDriver::blended`] instead.
+///
+/// Created with [`DisplayDriver::anti_aliased`].
+pub struct CoverageDrawTarget<'a, C, B: PanelBackend> {
+    driver: &'a mut DisplayDriver<C, B>,
+}
+
+impl<C: ToDisplayStorage, B: PanelBackend> DisplayDriver<C, B> {
+    /// Sets the colors that full and zero coverage are drawn in by the
+    /// [`anti_aliased`](Self::anti_aliased) draw target.
+    ///
+    /// They are white and black until this is called. Nothing already drawn is changed.
+    pub fn set_aa_colors(&mut self, foreground: C, background: C) {
+        self.aa_colors = (
+            foreground.to_display_storage(),
+            background.to_display_storage(),
+        );
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Returns a draw target for drawing anti-aliased [`Gray8`] art in the [anti-aliasing
+    /// colors](Self::set_aa_colors).
+    ///
+    /// See [`CoverageDrawTarget`].
+    pub fn anti_aliased(&mut self) -> CoverageDrawTarget<'_, C, B> {
+        CoverageDrawTarget { driver: self }
+    }
+}
+
+impl<C, B: PanelBackend> OriginDimensions for CoverageDrawTarget<'_, C, B> {
+    fn size(&self) -> Size {
+        self.driver.size()
+    }
+}
+
+impl<C, B: PanelBackend> DrawTarget for CoverageDrawTarget<'_, C, B> {
+    type Color = Gray8;
+
+    type Error = DisplayError;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(pos, coverage) in pixels {
+            if let Some(pos) = self.driver.to_screen(pos) {
+                self.driver
+                    .write_pixel(pos, storage(self.driver.aa_colors, coverage));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let aa_colors = self.driver.aa_colors;
+        let colors = colors
+            .into_iter()
+            .map(|coverage| storage(aa_colors, coverage));
+        if self.driver.fill_contiguous_storage(area, colors) {
+            Ok(())
+        } else {
+            Err(DisplayError::ColorBufferTooSmall)
+        }
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.driver
+            .fill_solid_storage(area, storage(self.driver.aa_colors, color));
+
+        Ok(())
+    }
+}
+
+/// Returns the color `coverage` is drawn in, given the `(foreground, background)` anti-aliasing
+/// colors.
+fn storage((foreground, background): (u32, u32), coverage: Gray8) -> u32 {
+    match coverage.luma() {
+        255 => foreground,
+        0 => background,
+        luma => color_math::mix(
+            Rgb888::from(RawU24::new(background)),
+            Rgb888::from(RawU24::new(foreground)),
+            luma,
+        )
+        .into_storage(),
+    }
+}
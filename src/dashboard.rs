@@ -0,0 +1,356 @@
+//! A retained set of widgets that only redraws what changed.
+
+use embedded_graphics::{
+    mono_font::{MonoFont, MonoTextStyle, ascii::FONT_6X10},
+    prelude::*,
+    primitives::{Arc, PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+};
+
+/// The colors and font a [`Widget`] is drawn with.
+#[derive(Debug, Clone, Copy)]
+pub struct WidgetStyle<C> {
+    /// Color of the widget's value and text.
+    pub foreground: C,
+    /// Color the widget's area is cleared to before it is drawn.
+    pub background: C,
+    /// Font used by text fields.
+    pub font: &'static MonoFont<'static>,
+}
+
+impl<C> WidgetStyle<C> {
+    /// Creates a new widget style that uses a 6x10 pixel font for text.
+    #[must_use]
+    pub const fn new(foreground: C, background: C) -> Self {
+        Self {
+            foreground,
+            background,
+            font: &FONT_6X10,
+        }
+    }
+}
+
+/// What a [`Widget`] shows.
+#[derive(Debug, Clone, PartialEq)]
+enum WidgetKind {
+    ProgressBar { value: f32 },
+    Gauge { value: f32 },
+    TextField { text: String },
+}
+
+/// A widget on a [`Dashboard`].
+///
+/// Widgets remember whether they've changed since they were last drawn, so changing one through
+/// [`set_value`](Self::set_value) or [`set_text`](Self::set_text) only redraws that widget on the
+/// next [`Dashboard::draw`]. Setting the value a widget already has doesn't redraw it.
+#[derive(Debug, Clone)]
+pub struct Widget<C> {
+    area: Rectangle,
+    style: WidgetStyle<C>,
+    kind: WidgetKind,
+    dirty: bool,
+}
+
+impl<C: PixelColor> Widget<C> {
+    fn new(area: Rectangle, style: WidgetStyle<C>, kind: WidgetKind) -> Self {
+        Self {
+            area,
+            style,
+            kind,
+            dirty: true,
+        }
+    }
+
+    /// Creates a horizontal bar that fills from the left as its value goes from 0 to 1.
+    #[must_use]
+    pub fn progress_bar(area: Rectangle, style: WidgetStyle<C>) -> Self {
+        Self::new(area, style, WidgetKind::ProgressBar { value: 0.0 })
+    }
+
+    /// Creates a round gauge whose arc sweeps clockwise as its value goes from 0 to 1.
+    #[must_use]
+    pub fn gauge(area: Rectangle, style: WidgetStyle<C>) -> Self {
+        Self::new(area, style, WidgetKind::Gauge { value: 0.0 })
+    }
+
+    /// Creates a single line of text, vertically centered in `area`.
+    #[must_use]
+    pub fn text_field(area: Rectangle, style: WidgetStyle<C>) -> Self {
+        Self::new(
+            area,
+            style,
+            WidgetKind::TextField {
+                text: String::new(),
+            },
+        )
+    }
+
+    /// Returns the area the widget is drawn in.
+    #[must_use]
+    pub const fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// Returns `true` if the widget has changed since it was last drawn.
+    #[must_use]
+    pub const fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Marks the widget as needing to be redrawn.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Returns the value of a progress bar or gauge, or `None` for a text field.
+    #[must_use]
+    pub fn value(&self) -> Option<f32> {
+        match self.kind {
+            WidgetKind::ProgressBar { value } | WidgetKind::Gauge { value } => Some(value),
+            WidgetKind::TextField { .. } => None,
+        }
+    }
+
+    /// Sets the value of a progress bar or gauge, clamped between 0 and 1.
+    ///
+    /// This has no effect on text fields.
+    pub fn set_value(&mut self, new_value: f32) {
+        let new_value = new_value.clamp(0.0, 1.0);
+        if let WidgetKind::ProgressBar { value } | WidgetKind::Gauge { value } = &mut self.kind
+            && *value != new_value
+        {
+            *value = new_value;
+            self.dirty = true;
+        }
+    }
+
+    /// Returns the text of a text field, or `None` for other widgets.
+    #[must_use]
+    pub fn text(&self) -> Option<&str> {
+        match &self.kind {
+            WidgetKind::TextField { text } => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Sets the text of a text field.
+    ///
+    /// This has no effect on other widgets.
+    pub fn set_text(&mut self, new_text: &str) {
+        if let WidgetKind::TextField { text } = &mut self.kind
+            && text != new_text
+        {
+            new_text.clone_into(text);
+            self.dirty = true;
+        }
+    }
+
+    /// Draws the widget, whether or not it has changed.
+    fn draw<D: DrawTarget<Color = C>>(&self, target: &mut D) -> Result<(), D::Error> {
+        let style = &self.style;
+        target.fill_solid(&self.area, style.background)?;
+
+        match &self.kind {
+            WidgetKind::ProgressBar { value } => {
+                let width = (self.area.size.width as f32 * value) as u32;
+                let filled =
+                    Rectangle::new(self.area.top_left, Size::new(width, self.area.size.height));
+                target.fill_solid(&filled, style.foreground)?;
+            }
+            WidgetKind::Gauge { value } => {
+                let diameter = self.area.size.width.min(self.area.size.height);
+                let stroke = (diameter / 8).max(1);
+                // Leave room for the stroke, which is centered on the arc.
+                Arc::with_center(
+                    self.area.center(),
+                    diameter.saturating_sub(stroke),
+                    135.0.deg(),
+                    (270.0 * value).deg(),
+                )
+                .into_styled(PrimitiveStyle::with_stroke(style.foreground, stroke))
+                .draw(target)?;
+            }
+            WidgetKind::TextField { text } => {
+                let position = Point::new(self.area.top_left.x, self.area.center().y);
+                let text_style = MonoTextStyle::new(style.font, style.foreground);
+                Text::with_baseline(text, position, text_style, Baseline::Middle).draw(target)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Identifies a widget on a [`Dashboard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WidgetId(usize);
+
+/// A retained collection of [widgets](Widget) that only redraws the ones that changed.
+///
+/// Rather than redrawing every widget each frame, build a dashboard once, update its widgets as
+/// values come in, and call [`draw`](Self::draw) every frame. Only widgets that changed since the
+/// last draw are redrawn, which works well with [`DisplayDriver::render_if_dirty`] since frames
+/// where nothing changed draw nothing.
+///
+/// [`DisplayDriver::render_if_dirty`]: crate::DisplayDriver::render_if_dirty
+#[derive(Debug, Clone)]
+pub struct Dashboard<C> {
+    widgets: Vec<Widget<C>>,
+}
+
+impl<C: PixelColor> Dashboard<C> {
+    /// Creates an empty dashboard.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            widgets: Vec::new(),
+        }
+    }
+
+    /// Adds a widget to the dashboard, returning its ID.
+    ///
+    /// Widgets are drawn in the order they were added, so later widgets are drawn over earlier
+    /// ones where they overlap.
+    pub fn add(&mut self, widget: Widget<C>) -> WidgetId {
+        self.widgets.push(widget);
+        WidgetId(self.widgets.len() - 1)
+    }
+
+    /// Returns the widget with the given ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is from a different dashboard and out of range for this one.
+    #[must_use]
+    pub fn widget(&self, id: WidgetId) -> &Widget<C> {
+        &self.widgets[id.0]
+    }
+
+    /// Returns the widget with the given ID for updating.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is from a different dashboard and out of range for this one.
+    pub fn widget_mut(&mut self, id: WidgetId) -> &mut Widget<C> {
+        &mut self.widgets[id.0]
+    }
+
+    /// Marks every widget as needing to be redrawn, for example after the screen was cleared.
+    pub fn mark_all_dirty(&mut self) {
+        for widget in &mut self.widgets {
+            widget.mark_dirty();
+        }
+    }
+
+    /// Draws every widget that changed since the last draw, returning how many were drawn.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from `target`. Widgets that weren't drawn yet stay dirty.
+    pub fn draw<D: DrawTarget<Color = C>>(&mut self, target: &mut D) -> Result<usize, D::Error> {
+        let mut drawn = 0;
+        for widget in self.widgets.iter_mut().filter(|widget| widget.dirty) {
+            widget.draw(target)?;
+            widget.dirty = false;
+            drawn += 1;
+        }
+
+        Ok(drawn)
+    }
+}
+
+impl<C: PixelColor> Default for Dashboard<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::pixelcolor::Rgb888;
+
+    use super::*;
+    use crate::{DisplayDriver, MockPanel};
+
+    const STYLE: WidgetStyle<Rgb888> = WidgetStyle::new(Rgb888::WHITE, Rgb888::BLUE);
+
+    /// A display with a dashboard of two progress bars and a text field, drawn once.
+    fn drawn_dashboard() -> (
+        DisplayDriver<Rgb888, MockPanel>,
+        Dashboard<Rgb888>,
+        [WidgetId; 3],
+    ) {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let mut dashboard = Dashboard::new();
+        let ids = [
+            dashboard.add(Widget::progress_bar(
+                Rectangle::new(Point::new(0, 0), Size::new(100, 10)),
+                STYLE,
+            )),
+            dashboard.add(Widget::progress_bar(
+                Rectangle::new(Point::new(0, 20), Size::new(100, 10)),
+                STYLE,
+            )),
+            dashboard.add(Widget::text_field(
+                Rectangle::new(Point::new(0, 40), Size::new(100, 12)),
+                STYLE,
+            )),
+        ];
+        assert_eq!(dashboard.draw(&mut display), Ok(3));
+        display.render();
+        (display, dashboard, ids)
+    }
+
+    #[test]
+    fn only_changed_widgets_are_redrawn() {
+        let (mut display, mut dashboard, [first, second, _]) = drawn_dashboard();
+
+        dashboard.widget_mut(second).set_value(0.5);
+        assert!(!dashboard.widget(first).is_dirty());
+        assert!(dashboard.widget(second).is_dirty());
+        assert_eq!(dashboard.draw(&mut display), Ok(1));
+
+        assert_eq!(
+            display.dirty_area(),
+            Some(Rectangle::new(Point::new(0, 20), Size::new(100, 10)))
+        );
+        assert_eq!(display.backend().pixel(49, 25), Some(0xFFFFFF));
+        assert_eq!(display.backend().pixel(50, 25), Some(0x0000FF));
+        assert_eq!(display.backend().pixel(0, 5), Some(0x0000FF));
+    }
+
+    #[test]
+    fn unchanged_values_do_not_redraw() {
+        let (mut display, mut dashboard, [first, _, text]) = drawn_dashboard();
+
+        dashboard.widget_mut(first).set_value(0.0);
+        dashboard.widget_mut(first).set_value(-3.0);
+        dashboard.widget_mut(text).set_text("");
+
+        assert_eq!(dashboard.draw(&mut display), Ok(0));
+        assert_eq!(display.dirty_area(), None);
+    }
+
+    #[test]
+    fn values_only_apply_to_their_kind_of_widget() {
+        let (_, mut dashboard, [first, _, text]) = drawn_dashboard();
+
+        dashboard.widget_mut(text).set_value(0.5);
+        dashboard.widget_mut(first).set_text("ignored");
+        dashboard.widget_mut(first).set_value(7.0);
+
+        assert_eq!(dashboard.widget(text).value(), None);
+        assert_eq!(dashboard.widget(first).text(), None);
+        assert_eq!(dashboard.widget(first).value(), Some(1.0));
+        assert!(!dashboard.widget(text).is_dirty());
+    }
+
+    #[test]
+    fn mark_all_dirty_redraws_everything() {
+        let (mut display, mut dashboard, _) = drawn_dashboard();
+
+        dashboard.mark_all_dirty();
+
+        assert_eq!(dashboard.draw(&mut display), Ok(3));
+    }
+}
@@ -0,0 +1,184 @@
+//! Tracking which part of the screen has changed since the last render.
+
+use embedded_graphics_core::primitives::Rectangle;
+
+use crate::{DisplayDriver, PanelBackend};
+
+/// The bounding box of everything drawn since the last render.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct DirtyRegion {
+    area: Option<Rectangle>,
+    suspended: bool,
+}
+
+impl DirtyRegion {
+    /// Grows the region to cover `area`, unless tracking is suspended.
+    pub(crate) fn include(&mut self, area: &Rectangle) {
+        let Some(bottom_right) = area.bottom_right() else {
+            return;
+        };
+        if self.suspended {
+            return;
+        }
+
+        self.area = Some(match self.area {
+            Some(dirty) => {
+                // `dirty` is never zero sized, so it always has a bottom right corner.
+                let dirty_bottom_right = dirty.bottom_right().unwrap_or(dirty.top_left);
+                Rectangle::with_corners(
+                    dirty.top_left.component_min(area.top_left),
+                    dirty_bottom_right.component_max(bottom_right),
+                )
+            }
+            None => *area,
+        });
+    }
+
+    /// Returns the bounding box, or `None` if nothing is dirty.
+    pub(crate) const fn area(&self) -> Option<Rectangle> {
+        self.area
+    }
+
+    /// Marks the region as clean.
+    pub(crate) fn clear(&mut self) {
+        self.area = None;
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Returns the bounding box of everything drawn since the last render, or `None` if nothing
+    /// has been drawn.
+    ///
+    /// The area is in screen coordinates, where `(0, 0)` is the top left of the area below the
+    /// header regardless of the [origin](Self::set_origin) and [insets](Self::set_insets).
+    #[must_use]
+    pub const fn dirty_area(&self) -> Option<Rectangle> {
+        self.dirty.area
+    }
+
+    /// Returns `true` if anything has been drawn since the last render.
+    #[must_use]
+    pub const fn is_dirty(&self) -> bool {
+        self.dirty.area.is_some()
+    }
+
+    /// Marks the whole screen as needing to be rendered.
+    pub fn mark_dirty(&mut self) {
+        self.dirty.area = Some(self.screen_bounds());
+    }
+
+    /// [Renders](Self::render) the display only if anything has been drawn since the last render.
+    ///
+    /// Returns `true` if the display was rendered. This lets a loop that only redraws on changes
+    /// call it every iteration without paying for a flush when nothing happened.
+    pub fn render_if_dirty(&mut self) -> bool {
+        if !self.is_dirty() {
+            return false;
+        }
+
+        self.render();
+        true
+    }
+
+    /// Like [`render_if_dirty`](Self::render_if_dirty), but only returns `true` if the double
+    /// buffer was actually flushed to the panel.
+    ///
+    /// In [`RenderMode::Immediate`](crate::RenderMode::Immediate) draws show up as they happen
+    /// and there is no buffer to flush, so this always returns `false`, even though anything
+    /// waiting in the [managed region](Self::set_managed_region) is still copied to the panel.
+    /// That makes it a clear signal for loops and tests that expect each frame to be presented.
+    pub fn try_render(&mut self) -> bool {
+        if !self.is_dirty() {
+            return false;
+        }
+
+        let flushed = self.is_double_buffered();
+        self.render();
+        flushed
+    }
+
+    /// Runs `f` without tracking what it draws, then marks the whole screen as dirty.
+    ///
+    /// Growing the dirty area on every draw is wasted work when you already know the whole screen
+    /// is being redrawn. Draws inside `f` skip it, and the whole screen is marked dirty once `f`
+    /// returns, so the next [`render_if_dirty`](Self::render_if_dirty) always renders, even if `f`
+    /// didn't draw anything.
+    pub fn with_full_redraw<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let suspended = core::mem::replace(&mut self.dirty.suspended, true);
+        let result = f(self);
+        self.dirty.suspended = suspended;
+        self.mark_dirty();
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::{pixelcolor::Rgb888, prelude::*};
+
+    use super::*;
+    use crate::{MockPanel, RenderMode};
+
+    #[test]
+    fn draws_grow_the_dirty_area() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.set_origin(Point::new(100, 50));
+        assert_eq!(display.dirty_area(), None);
+
+        display.set_pixel(Point::new(1, 2), Rgb888::RED);
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(10, 10), Size::new(5, 5)),
+                Rgb888::RED,
+            )
+            .unwrap();
+
+        assert_eq!(
+            display.dirty_area(),
+            Some(Rectangle::with_corners(
+                Point::new(101, 52),
+                Point::new(114, 64)
+            ))
+        );
+    }
+
+    #[test]
+    fn with_full_redraw_marks_the_whole_screen_dirty() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        display.with_full_redraw(|display| {
+            display.set_pixel(Point::new(1, 2), Rgb888::RED);
+            assert_eq!(display.dirty_area(), None);
+        });
+
+        assert_eq!(display.dirty_area(), Some(display.screen_bounds()));
+        assert_eq!(display.backend().pixel(1, 2), Some(0xFF0000));
+    }
+
+    #[test]
+    fn with_full_redraw_renders_even_without_draws() {
+        let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+        display.set_render_mode(RenderMode::DoubleBuffered);
+
+        display.with_full_redraw(|_| {});
+
+        assert!(display.render_if_dirty());
+        assert!(!display.render_if_dirty());
+        assert_eq!(display.backend().renders(), 1);
+    }
+
+    #[test]
+    fn tracking_resumes_after_with_full_redraw() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.with_full_redraw(|_| {});
+        display.render();
+
+        display.set_pixel(Point::new(3, 4), Rgb888::RED);
+
+        assert_eq!(
+            display.dirty_area(),
+            Some(Rectangle::new(Point::new(3, 4), Size::new(1, 1)))
+        );
+    }
+}
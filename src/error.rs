@@ -0,0 +1,41 @@
+//! Errors reported by the driver.
+
+use core::fmt;
+
+/// An error that occurred while drawing to the display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DisplayError {
+    /// The area being drawn to has no width or height, or its far corner doesn't fit in an `i32`.
+    InvalidArea,
+
+    /// The area being drawn to is entirely outside of the drawable part of the display.
+    AreaOutOfBounds,
+
+    /// The source image has a width of zero or doesn't contain a single full row.
+    InvalidImage,
+
+    /// The operation needs the shadow buffer, which isn't enabled.
+    NoShadowBuffer,
+
+    /// Fewer colors were given than there are pixels in the area being filled.
+    ColorBufferTooSmall,
+
+    /// The operation isn't possible in the display's current [render mode](crate::RenderMode).
+    UnsupportedRenderMode,
+}
+
+impl fmt::Display for DisplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::InvalidArea => "the area has no pixels or its corners overflow",
+            Self::AreaOutOfBounds => "the area is entirely outside of the display",
+            Self::InvalidImage => "the source image has no full rows",
+            Self::NoShadowBuffer => "the shadow buffer is not enabled",
+            Self::ColorBufferTooSmall => "there are fewer colors than pixels in the area",
+            Self::UnsupportedRenderMode => "the operation isn't possible in this render mode",
+        })
+    }
+}
+
+impl core::error::Error for DisplayError {}
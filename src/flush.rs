@@ -0,0 +1,38 @@
+//! How rendered frames get onto the panel.
+
+use crate::{DisplayDriver, PanelBackend, RenderMode};
+
+/// The way [`DisplayDriver::render`] gets a frame onto the panel.
+///
+/// See [`DisplayDriver::flush_method`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FlushMethod {
+    /// Draws go straight to the panel, so rendering has nothing to do.
+    ///
+    /// This is the method in [`RenderMode::Immediate`].
+    Direct,
+
+    /// Draws go to a back buffer, and each render copies the whole back buffer to the panel.
+    ///
+    /// This is the method in [`RenderMode::DoubleBuffered`]. The copy takes time proportional to
+    /// the size of the panel, however little changed in the frame.
+    Copy,
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Returns how [`render`](Self::render) currently gets frames onto the panel, to help reason
+    /// about how much a render costs.
+    ///
+    /// The VEX SDK only offers a single back buffer that it copies to the panel on each render;
+    /// it has no way to flip between two buffers in place, so double buffered rendering is
+    /// always [`FlushMethod::Copy`]. [`FlushMethod`] is non-exhaustive so that a cheaper method
+    /// can be added if the SDK ever supports one.
+    #[must_use]
+    pub fn flush_method(&self) -> FlushMethod {
+        match self.render_mode() {
+            RenderMode::Immediate => FlushMethod::Direct,
+            RenderMode::DoubleBuffered => FlushMethod::Copy,
+        }
+    }
+}
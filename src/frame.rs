@@ -0,0 +1,459 @@
+//! The mapping between embedded-graphics coordinates and the screen.
+
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use crate::{DisplayDriver, HEADER_HEIGHT, PANEL_HEIGHT, WIDTH};
+
+/// Space to leave unused along each edge of the display.
+///
+/// See [`DisplayDriver::set_insets`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Insets {
+    /// Rows to leave unused along the top edge.
+    pub top: u32,
+    /// Columns to leave unused along the right edge.
+    pub right: u32,
+    /// Rows to leave unused along the bottom edge.
+    pub bottom: u32,
+    /// Columns to leave unused along the left edge.
+    pub left: u32,
+}
+
+impl Insets {
+    /// Insets that leave the whole display usable.
+    pub const ZERO: Self = Self::uniform(0);
+
+    /// Creates new insets, in the same order as CSS margins.
+    #[must_use]
+    pub const fn new(top: u32, right: u32, bottom: u32, left: u32) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    /// Creates insets that are the same on every edge.
+    #[must_use]
+    pub const fn uniform(inset: u32) -> Self {
+        Self::new(inset, inset, inset, inset)
+    }
+}
+
+impl<C, B> DisplayDriver<C, B> {
+    /// Height of the status bar that VEXos draws at the top of the panel, and the default
+    /// [header offset](Self::with_header_offset).
+    pub const STATUS_BAR_HEIGHT: u32 = HEADER_HEIGHT;
+
+    /// Sets the number of rows at the top of the panel that the firmware reserves for its header.
+    ///
+    /// The default of [`STATUS_BAR_HEIGHT`](Self::STATUS_BAR_HEIGHT) matches current VEXos, which
+    /// draws its status bar above the area that user programs can draw to. Firmware that reserves
+    /// a different number of rows also moves and resizes the drawable area, so the driver
+    /// translates every draw and touch point to keep embedded-graphics' `(0, 0)` just below the
+    /// header, and its [`size`](OriginDimensions) covers the rest of the panel.
+    ///
+    /// Any enabled shadow buffer is cleared, since it has to be resized for the new area.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` leaves no rows to draw to, i.e. if it is 272 or more.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_graphics_core::prelude::*;
+    /// use vexide_embedded_graphics::{DisplayDriver, MockPanel};
+    ///
+    /// let display = DisplayDriver::new(MockPanel::new()).with_header_offset(0);
+    /// assert_eq!(display.size(), Size::new(480, 272));
+    /// ```
+    #[must_use]
+    pub fn with_header_offset(mut self, offset: u32) -> Self {
+        self.set_header_offset(offset);
+        self
+    }
+
+    /// Changes the header offset in place, as described for
+    /// [`with_header_offset`](Self::with_header_offset).
+    pub(crate) fn set_header_offset(&mut self, offset: u32) {
+        assert!(
+            offset < PANEL_HEIGHT,
+            "header offset {offset} must be less than the panel height of {PANEL_HEIGHT}"
+        );
+
+        self.header_offset = offset;
+        let len = WIDTH as usize * self.screen_height() as usize;
+        if let Some(shadow) = &mut self.shadow {
+            *shadow = vec![0; len].into_boxed_slice();
+        }
+        self.reset_layers();
+    }
+
+    /// Returns the number of rows reserved for the firmware's header.
+    #[must_use]
+    pub const fn header_offset(&self) -> u32 {
+        self.header_offset
+    }
+
+    /// Sets the screen position that embedded-graphics' `(0, 0)` is drawn at.
+    ///
+    /// The origin is relative to the top left of the [safe area](Self::set_insets). Anything
+    /// above or to the left of it can't be drawn to, so the driver's [`size`](OriginDimensions)
+    /// shrinks by the offset and layout code using `bounding_box()` keeps working. Negative
+    /// components are treated as zero.
+    pub fn set_origin(&mut self, origin: Point) {
+        self.origin = origin.component_max(Point::zero());
+    }
+
+    /// Returns the current origin offset.
+    #[must_use]
+    pub const fn origin(&self) -> Point {
+        self.origin
+    }
+
+    /// Sets the space to leave unused along each edge of the display.
+    ///
+    /// This is useful when part of the screen is hidden behind a bezel or covered by something
+    /// mounted on the robot. Draws are clipped to the remaining safe area, and the driver's
+    /// [`size`](OriginDimensions) and `bounding_box()` describe it rather than the whole panel.
+    pub fn set_insets(&mut self, insets: Insets) {
+        self.insets = insets;
+    }
+
+    /// Returns the current insets.
+    #[must_use]
+    pub const fn insets(&self) -> Insets {
+        self.insets
+    }
+
+    /// Returns the height of the area below the header.
+    pub(crate) const fn screen_height(&self) -> u32 {
+        PANEL_HEIGHT - self.header_offset
+    }
+
+    /// Returns the area below the header, in screen coordinates.
+    pub(crate) fn screen_bounds(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), Size::new(WIDTH, self.screen_height()))
+    }
+
+    /// Maps a screen row to the SDK's coordinates, which always start 32 rows down the panel.
+    pub(crate) const fn sdk_y(&self, y: i32) -> i32 {
+        y + self.header_offset as i32 - HEADER_HEIGHT as i32
+    }
+
+    /// Returns the part of the screen that can be drawn to, in screen coordinates.
+    pub(crate) fn drawable_area(&self) -> Rectangle {
+        let height = self.screen_height();
+        let left = (self.insets.left.saturating_add(self.origin.x as u32)).min(WIDTH);
+        let top = (self.insets.top.saturating_add(self.origin.y as u32)).min(height);
+        let right = WIDTH.saturating_sub(self.insets.right).max(left);
+        let bottom = height.saturating_sub(self.insets.bottom).max(top);
+
+        Rectangle::new(
+            Point::new(left as i32, top as i32),
+            Size::new(right - left, bottom - top),
+        )
+    }
+
+    /// Returns the size of the drawable area in embedded-graphics coordinates, which is
+    /// [rotated](Self::set_rotation) from its size on screen.
+    pub(crate) fn logical_size(&self) -> Size {
+        let size = self.drawable_area().size;
+        if self.rotation.is_sideways() {
+            Size::new(size.height, size.width)
+        } else {
+            size
+        }
+    }
+
+    /// Maps a point to screen coordinates, or `None` if it falls outside the drawable area.
+    pub(crate) fn to_screen(&self, point: Point) -> Option<Point> {
+        // Points outside the drawable area are turned away before they are oriented, since
+        // reflecting coordinates far off screen could overflow.
+        let size = self.logical_size();
+        let (Ok(x), Ok(y)) = (u32::try_from(point.x), u32::try_from(point.y)) else {
+            return None;
+        };
+        if x >= size.width || y >= size.height {
+            return None;
+        }
+
+        let area = self.drawable_area();
+        Some(self.orient(point, area.size) + area.top_left)
+    }
+
+    /// Maps a point from screen coordinates to embedded-graphics coordinates, even if it is
+    /// outside the drawable area.
+    pub(crate) fn to_logical(&self, point: Point) -> Point {
+        let area = self.drawable_area();
+        self.unorient(point - area.top_left, area.size)
+    }
+
+    /// Maps a rectangle that lies within the drawable area to screen coordinates.
+    pub(crate) fn screen_rect(&self, area: &Rectangle) -> Rectangle {
+        let drawable = self.drawable_area();
+        let top_left = self.orient(area.top_left, drawable.size) + drawable.top_left;
+        let Some(bottom_right) = area.bottom_right() else {
+            return Rectangle::new(top_left, Size::zero());
+        };
+
+        let bottom_right = self.orient(bottom_right, drawable.size) + drawable.top_left;
+        Rectangle::with_corners(top_left, bottom_right)
+    }
+
+    /// Maps a rectangle to screen coordinates, clipped to the drawable area.
+    ///
+    /// The result is zero sized if nothing of `area` is visible.
+    pub(crate) fn clip_to_screen(&self, area: &Rectangle) -> Rectangle {
+        let visible = safe_intersection(area, &self.bounding_box());
+        if visible.is_zero_sized() {
+            return visible;
+        }
+
+        self.screen_rect(&visible)
+    }
+}
+
+/// Returns the part of `area` that is inside `bounds`, like [`Rectangle::intersection`].
+///
+/// embedded-graphics works out the bottom right corner of both rectangles, which overflows for
+/// rectangles that reach past `i32::MAX`, like an area of `Size::new(u32::MAX, u32::MAX)` meant
+/// as "everything". This works with wider integers instead, so any `area` can be clipped to
+/// `bounds`, which has to be a rectangle whose corners fit in an `i32`. The result is
+/// [`Rectangle::zero`] if the two don't overlap.
+pub(crate) fn safe_intersection(area: &Rectangle, bounds: &Rectangle) -> Rectangle {
+    let start = |rect: &Rectangle| (i64::from(rect.top_left.x), i64::from(rect.top_left.y));
+    let end = |rect: &Rectangle| {
+        let (x, y) = start(rect);
+        (
+            x + i64::from(rect.size.width),
+            y + i64::from(rect.size.height),
+        )
+    };
+
+    let (left, top) = (
+        start(area).0.max(start(bounds).0),
+        start(area).1.max(start(bounds).1),
+    );
+    let (right, bottom) = (
+        end(area).0.min(end(bounds).0),
+        end(area).1.min(end(bounds).1),
+    );
+    if left >= right || top >= bottom {
+        return Rectangle::zero();
+    }
+
+    Rectangle::new(
+        Point::new(left as i32, top as i32),
+        Size::new((right - left) as u32, (bottom - top) as u32),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::pixelcolor::Rgb888;
+
+    use super::*;
+    use crate::{GradientDirection, MockPanel, Rotation};
+
+    const ROTATIONS: [Rotation; 4] = [
+        Rotation::Deg0,
+        Rotation::Deg90,
+        Rotation::Deg180,
+        Rotation::Deg270,
+    ];
+
+    /// Points on and far off the screen, including the extremes of an `i32`.
+    fn extreme_points() -> impl Iterator<Item = Point> {
+        const VALUES: [i32; 8] = [
+            i32::MIN,
+            i32::MIN + 1,
+            -100_000,
+            -1,
+            0,
+            100,
+            i32::MAX - 1,
+            i32::MAX,
+        ];
+        VALUES
+            .into_iter()
+            .flat_map(|x| VALUES.into_iter().map(move |y| Point::new(x, y)))
+    }
+
+    const EXTREME_SIZES: [Size; 5] = [
+        Size::zero(),
+        Size::new(10, 10),
+        Size::new(u32::MAX, u32::MAX),
+        Size::new(u32::MAX / 2 + 1, 3),
+        Size::new(3, u32::MAX),
+    ];
+
+    /// Runs `draw` on a fresh display under every rotation.
+    fn for_each_rotation(mut draw: impl FnMut(&mut DisplayDriver<Rgb888, MockPanel>)) {
+        for rotation in ROTATIONS {
+            let mut display = DisplayDriver::new(MockPanel::new());
+            display.set_rotation(rotation);
+            draw(&mut display);
+        }
+    }
+
+    #[test]
+    fn safe_intersection_handles_areas_whose_corners_overflow() {
+        let bounds = Rectangle::new(Point::new(0, 0), Size::new(480, 240));
+        let huge = Rectangle::new(
+            Point::new(i32::MAX, i32::MAX),
+            Size::new(u32::MAX, u32::MAX),
+        );
+        assert_eq!(safe_intersection(&huge, &bounds), Rectangle::zero());
+
+        let everything = Rectangle::new(
+            Point::new(i32::MIN, i32::MIN),
+            Size::new(u32::MAX, u32::MAX),
+        );
+        assert_eq!(safe_intersection(&everything, &bounds), bounds);
+    }
+
+    #[test]
+    fn extreme_rectangles_are_clipped() {
+        for top_left in extreme_points() {
+            for size in EXTREME_SIZES {
+                let area = Rectangle::new(top_left, size);
+                for_each_rotation(|display| {
+                    display.fill_solid(&area, Rgb888::RED).unwrap();
+                    let colors = core::iter::repeat_n(Rgb888::RED, 1000);
+                    let _ = display.fill_contiguous(&area, colors);
+                    for direction in [
+                        GradientDirection::Vertical,
+                        GradientDirection::Horizontal,
+                        GradientDirection::Diagonal,
+                    ] {
+                        display.fill_gradient(&area, Rgb888::RED, Rgb888::BLUE, direction);
+                    }
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn extreme_shapes_are_clipped() {
+        for point in extreme_points() {
+            let mirrored = Point::new(point.y, point.x);
+            for_each_rotation(|display| {
+                display.draw_line(point, Point::new(10, 10), 3, Rgb888::RED);
+                display.draw_line(point, mirrored, u32::MAX, Rgb888::RED);
+                display.fill_triangle(point, Point::new(10, 10), mirrored, Rgb888::RED);
+                display.fill_circle(point, 5, Rgb888::RED);
+                display.fill_circle(point, u32::MAX, Rgb888::RED);
+                display.draw_circle(point, u32::MAX, 3, Rgb888::RED);
+            });
+        }
+    }
+
+    #[test]
+    fn extreme_points_and_viewports_are_clipped() {
+        for point in extreme_points() {
+            for size in EXTREME_SIZES {
+                let area = Rectangle::new(point, size);
+                for_each_rotation(|display| {
+                    display.set_pixel(point, Rgb888::RED);
+                    display.draw_iter([Pixel(point, Rgb888::RED)]).unwrap();
+                    let _ = display.touched_within(&area);
+                    display.fill_checkerboard(&area, Rgb888::RED, Rgb888::BLUE, 7);
+
+                    let mut viewport = display.viewport(area);
+                    viewport.fill_solid(&area, Rgb888::RED).unwrap();
+                    viewport.draw_iter([Pixel(point, Rgb888::RED)]).unwrap();
+                    viewport
+                        .viewport(area)
+                        .fill_solid(&area, Rgb888::RED)
+                        .unwrap();
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn an_area_covering_the_whole_i32_range_fills_the_screen() {
+        let everything = Rectangle::new(
+            Point::new(i32::MIN, i32::MIN),
+            Size::new(u32::MAX, u32::MAX),
+        );
+        for_each_rotation(|display| {
+            display.fill_solid(&everything, Rgb888::RED).unwrap();
+            assert!(
+                display
+                    .backend()
+                    .framebuffer()
+                    .iter()
+                    .all(|&color| color == 0xFF0000)
+            );
+        });
+    }
+
+    #[test]
+    fn bounding_box_reflects_the_origin_and_insets() {
+        let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+        display.set_origin(Point::new(20, 10));
+        assert_eq!(display.bounding_box().size, Size::new(460, 230));
+
+        display.set_insets(Insets::new(5, 30, 15, 40));
+        assert_eq!(
+            display.bounding_box(),
+            Rectangle::new(Point::zero(), Size::new(390, 210))
+        );
+
+        display.set_rotation(Rotation::Deg90);
+        assert_eq!(display.bounding_box().size, Size::new(210, 390));
+    }
+
+    #[test]
+    fn bounding_box_covers_exactly_the_drawable_area() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.set_origin(Point::new(20, 10));
+        display.set_insets(Insets::new(5, 30, 15, 40));
+
+        let area = display.bounding_box();
+        display.fill_solid(&area, Rgb888::RED).unwrap();
+
+        let panel = display.backend();
+        let drawn = panel
+            .framebuffer()
+            .iter()
+            .filter(|&&c| c == 0xFF0000)
+            .count();
+        assert_eq!(drawn, 390 * 210);
+        assert_eq!(panel.pixel(60, 15), Some(0xFF0000));
+        assert_eq!(panel.pixel(449, 224), Some(0xFF0000));
+        assert_eq!(panel.pixel(59, 15), Some(0));
+        assert_eq!(panel.pixel(60, 14), Some(0));
+        assert_eq!(panel.pixel(450, 224), Some(0));
+        assert_eq!(panel.pixel(449, 225), Some(0));
+    }
+
+    #[test]
+    fn insets_larger_than_the_screen_leave_nothing_to_draw() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.set_insets(Insets::new(200, 300, 200, 300));
+        display.set_origin(Point::new(-5, 1000));
+
+        assert_eq!(display.origin(), Point::new(0, 1000));
+        assert!(display.bounding_box().is_zero_sized());
+        let area = Rectangle::new(Point::zero(), Size::new(10, 10));
+        display.fill_solid(&area, Rgb888::RED).unwrap();
+        assert!(display.backend().framebuffer().iter().all(|&c| c == 0));
+    }
+
+    #[test]
+    fn size_grows_by_the_header_when_it_is_hidden() {
+        let display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+        assert_eq!(display.size(), Size::new(480, 240));
+
+        let mut fullscreen = display.with_header_offset(0);
+        assert_eq!(fullscreen.size(), Size::new(480, 272));
+
+        fullscreen.set_rotation(Rotation::Deg90);
+        assert_eq!(fullscreen.size(), Size::new(272, 480));
+    }
+}
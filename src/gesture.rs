@@ -0,0 +1,297 @@
+//! Recognizing taps and swipes on the touchscreen.
+
+use core::time::Duration;
+use std::time::Instant;
+
+use embedded_graphics_core::prelude::*;
+
+use crate::{Clock, DisplayDriver, PanelBackend, SystemClock};
+
+/// A gesture recognized by a [`GestureDetector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Gesture {
+    /// A touch that moved left far enough to count as a swipe before it was released.
+    SwipeLeft,
+    /// A touch that moved right far enough to count as a swipe before it was released.
+    SwipeRight,
+    /// A touch that moved up far enough to count as a swipe before it was released.
+    SwipeUp,
+    /// A touch that moved down far enough to count as a swipe before it was released.
+    SwipeDown,
+    /// A short touch that stayed in place.
+    Tap,
+    /// A touch held in place for the long press duration. This is reported while the touch is
+    /// still held, and releasing it afterwards doesn't report anything else.
+    LongPress,
+}
+
+/// Turns touch input into [`Gesture`]s.
+///
+/// Feed the detector the touch state once per frame with [`DisplayDriver::detect_gesture`], and
+/// it reports each gesture once, as soon as it is recognized. It keeps track of where each touch
+/// started, where it is now and how long it has been held:
+///
+/// - A touch that moves at least the [swipe distance](Self::with_swipe_distance) from where it
+///   started is a swipe in the direction it moved the most, reported when it is released.
+/// - A touch that stays closer than that is a [tap](Gesture::Tap) if it is released before the
+///   [long press duration](Self::with_long_press_duration), or a
+///   [long press](Gesture::LongPress) once it has been held that long.
+///
+/// Directions are in embedded-graphics coordinates, so they follow the display's
+/// [rotation](DisplayDriver::set_rotation). Touches are only sampled when the detector is fed, so
+/// a tap that starts and ends between two frames is missed.
+///
+/// # Examples
+///
+/// ```
+/// # use vexide::prelude::*;
+/// use vexide_embedded_graphics::{DisplayDriver, FramePacer, Gesture, GestureDetector};
+///
+/// # #[vexide::main]
+/// # async fn main(peripherals: Peripherals) {
+/// let mut display = DisplayDriver::new(peripherals.display);
+/// let mut gestures = GestureDetector::new();
+/// let mut pacer = FramePacer::native();
+/// let mut page = 0;
+///
+/// loop {
+///     match display.detect_gesture(&mut gestures) {
+///         Some(Gesture::SwipeLeft) => page += 1,
+///         Some(Gesture::SwipeRight) => page -= 1,
+///         _ => {}
+///     }
+///     // Draw the page...
+///     display.render();
+///     pacer.wait().await;
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GestureDetector<K = SystemClock> {
+    swipe_distance: u32,
+    long_press: Duration,
+    press: Option<Press>,
+    clock: K,
+}
+
+/// The touch a [`GestureDetector`] is following.
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    start: Point,
+    last: Point,
+    started: Instant,
+    long_press_reported: bool,
+}
+
+impl GestureDetector {
+    /// Creates a detector with a swipe distance of 40 pixels and a long press duration of half a
+    /// second.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_clock(SystemClock)
+    }
+}
+
+impl<K: Clock> GestureDetector<K> {
+    /// Creates a detector like [`new`](GestureDetector::new), reading the time from `clock`.
+    #[must_use]
+    pub const fn with_clock(clock: K) -> Self {
+        Self {
+            swipe_distance: 40,
+            long_press: Duration::from_millis(500),
+            press: None,
+            clock,
+        }
+    }
+
+    /// Sets how many pixels a touch has to move, along either axis, to count as a swipe.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `distance` is zero.
+    #[must_use]
+    pub const fn with_swipe_distance(mut self, distance: u32) -> Self {
+        assert!(distance > 0, "swipe distance must be greater than zero");
+        self.swipe_distance = distance;
+        self
+    }
+
+    /// Sets how long a touch has to be held in place to count as a long press.
+    #[must_use]
+    pub const fn with_long_press_duration(mut self, duration: Duration) -> Self {
+        self.long_press = duration;
+        self
+    }
+
+    /// Returns the distance set by [`with_swipe_distance`](Self::with_swipe_distance).
+    #[must_use]
+    pub const fn swipe_distance(&self) -> u32 {
+        self.swipe_distance
+    }
+
+    /// Returns the duration set by [`with_long_press_duration`](Self::with_long_press_duration).
+    #[must_use]
+    pub const fn long_press_duration(&self) -> Duration {
+        self.long_press
+    }
+
+    /// Forgets the touch being followed, so it doesn't turn into a gesture.
+    pub fn reset(&mut self) {
+        self.press = None;
+    }
+
+    /// Feeds the detector where the screen is being touched, or `None` if it isn't, returning the
+    /// gesture this completes.
+    ///
+    /// [`DisplayDriver::detect_gesture`] calls this with the display's touch state. Calling it
+    /// directly is useful for touch input that comes from somewhere else.
+    pub fn update(&mut self, touch: Option<Point>) -> Option<Gesture> {
+        let now = self.clock.now();
+        let Some(point) = touch else {
+            let press = self.press.take()?;
+            if press.long_press_reported {
+                return None;
+            }
+            return self.swipe(&press).or_else(|| {
+                (now.duration_since(press.started) < self.long_press).then_some(Gesture::Tap)
+            });
+        };
+
+        let press = self.press.get_or_insert(Press {
+            start: point,
+            last: point,
+            started: now,
+            long_press_reported: false,
+        });
+        press.last = point;
+
+        let press = *press;
+        if press.long_press_reported
+            || self.swipe(&press).is_some()
+            || now.duration_since(press.started) < self.long_press
+        {
+            return None;
+        }
+        if let Some(press) = &mut self.press {
+            press.long_press_reported = true;
+        }
+        Some(Gesture::LongPress)
+    }
+
+    /// Returns the swipe `press` has made so far, if it has moved far enough for one.
+    fn swipe(&self, press: &Press) -> Option<Gesture> {
+        let delta = press.last - press.start;
+        let (dx, dy) = (delta.x.unsigned_abs(), delta.y.unsigned_abs());
+        if dx.max(dy) < self.swipe_distance {
+            return None;
+        }
+
+        Some(match (dx >= dy, delta.x < 0, delta.y < 0) {
+            (true, true, _) => Gesture::SwipeLeft,
+            (true, false, _) => Gesture::SwipeRight,
+            (false, _, true) => Gesture::SwipeUp,
+            (false, _, false) => Gesture::SwipeDown,
+        })
+    }
+}
+
+impl Default for GestureDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Feeds the current touch state into `detector`, returning the gesture it completes.
+    ///
+    /// Call this once per frame. See [`GestureDetector`].
+    pub fn detect_gesture<K: Clock>(&self, detector: &mut GestureDetector<K>) -> Option<Gesture> {
+        detector.update(self.touch_point())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pacing::ManualClock;
+
+    const START: Point = Point::new(100, 100);
+
+    #[test]
+    fn a_short_touch_in_place_is_a_tap() {
+        let clock = ManualClock::new();
+        let mut detector = GestureDetector::with_clock(&clock);
+
+        assert_eq!(detector.update(Some(START)), None);
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(detector.update(Some(START + Point::new(3, -2))), None);
+        clock.advance(Duration::from_millis(100));
+
+        assert_eq!(detector.update(None), Some(Gesture::Tap));
+        assert_eq!(detector.update(None), None);
+    }
+
+    #[test]
+    fn a_long_press_is_reported_once_while_held() {
+        let clock = ManualClock::new();
+        let mut detector = GestureDetector::with_clock(&clock);
+
+        assert_eq!(detector.update(Some(START)), None);
+        clock.advance(Duration::from_millis(499));
+        assert_eq!(detector.update(Some(START)), None);
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(detector.update(Some(START)), Some(Gesture::LongPress));
+
+        clock.advance(Duration::from_millis(500));
+        assert_eq!(detector.update(Some(START)), None);
+        assert_eq!(detector.update(None), None);
+    }
+
+    #[test]
+    fn swipes_are_in_the_direction_moved_the_most() {
+        for (delta, gesture) in [
+            (Point::new(-40, 10), Gesture::SwipeLeft),
+            (Point::new(40, -10), Gesture::SwipeRight),
+            (Point::new(10, -40), Gesture::SwipeUp),
+            (Point::new(-10, 40), Gesture::SwipeDown),
+        ] {
+            let clock = ManualClock::new();
+            let mut detector = GestureDetector::with_clock(&clock);
+
+            assert_eq!(detector.update(Some(START)), None);
+            clock.advance(Duration::from_millis(100));
+            assert_eq!(detector.update(Some(START + delta)), None);
+            // Holding a swipe doesn't turn it into a long press.
+            clock.advance(Duration::from_millis(600));
+            assert_eq!(detector.update(Some(START + delta)), None);
+
+            assert_eq!(detector.update(None), Some(gesture), "moved by {delta:?}");
+        }
+    }
+
+    #[test]
+    fn moving_less_than_the_swipe_distance_is_not_a_swipe() {
+        let clock = ManualClock::new();
+        let mut detector = GestureDetector::with_clock(&clock);
+
+        assert_eq!(detector.update(Some(START)), None);
+        clock.advance(Duration::from_millis(50));
+        assert_eq!(detector.update(Some(START + Point::new(39, 39))), None);
+        clock.advance(Duration::from_millis(50));
+
+        assert_eq!(detector.update(None), Some(Gesture::Tap));
+    }
+
+    #[test]
+    fn a_touch_released_after_the_long_press_duration_is_not_a_tap() {
+        let clock = ManualClock::new();
+        let mut detector = GestureDetector::with_clock(&clock)
+            .with_long_press_duration(Duration::from_millis(200));
+
+        assert_eq!(detector.update(Some(START)), None);
+        clock.advance(Duration::from_millis(200));
+
+        // The touch wasn't sampled since it became a long press, so nothing was reported.
+        assert_eq!(detector.update(None), None);
+    }
+}
@@ -0,0 +1,288 @@
+//! Filling areas that only change color from row to row.
+
+use core::ops::Range;
+
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use crate::{DisplayDriver, PanelBackend, ToDisplayStorage, frame::safe_intersection};
+
+/// Which way a gradient drawn by [`DisplayDriver::fill_gradient`] runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GradientDirection {
+    /// From the top edge to the bottom edge.
+    Vertical,
+    /// From the left edge to the right edge.
+    Horizontal,
+    /// From the top left corner to the bottom right corner.
+    Diagonal,
+}
+
+impl<C: ToDisplayStorage, B: PanelBackend> DisplayDriver<C, B> {
+    /// Fills `area` one row at a time, with each row a single color from `row_colors`.
+    ///
+    /// This draws the same thing as a [`fill_contiguous`](DrawTarget::fill_contiguous) where each
+    /// color is repeated across its row, but each row is sent to the display as a single solid
+    /// fill instead of being copied pixel by pixel. Rows past the end of `row_colors` are left
+    /// untouched.
+    pub fn fill_rows<I>(&mut self, area: &Rectangle, row_colors: I)
+    where
+        I: IntoIterator<Item = C>,
+    {
+        self.fill_rows_storage(area, row_colors.into_iter().map(C::to_display_storage));
+    }
+
+    /// Fills `area` with a vertical gradient from `top` to `bottom`.
+    ///
+    /// The first row is exactly `top` and the last row is exactly `bottom`. This is
+    /// [`fill_gradient`](Self::fill_gradient) with [`GradientDirection::Vertical`].
+    pub fn fill_vertical_gradient(&mut self, area: &Rectangle, top: C, bottom: C) {
+        self.fill_gradient(area, top, bottom, GradientDirection::Vertical);
+    }
+
+    /// Fills `area` with a gradient from `start` to `end`, running in `direction`.
+    ///
+    /// The first row, column or corner pixel is exactly `start` and the last is exactly `end`.
+    /// Vertical and horizontal gradients are drawn as one solid fill per band of rows or columns
+    /// with the same color, so a gradient over a 200 pixel tall area takes at most 200 fills. A
+    /// diagonal gradient changes color along every row, so each of its rows is copied to the
+    /// display as a line of pixels instead. Only the part of `area` on screen is drawn, with the
+    /// colors it would have had as part of the whole gradient.
+    pub fn fill_gradient(
+        &mut self,
+        area: &Rectangle,
+        start: C,
+        end: C,
+        direction: GradientDirection,
+    ) {
+        let (start, end) = (start.to_display_storage(), end.to_display_storage());
+        let Size { width, height } = area.size;
+
+        // Only the visible part of the area is drawn, but colors are still worked out from each
+        // row and column's position in the whole area.
+        let visible = safe_intersection(area, &self.bounding_box());
+        if visible.is_zero_sized() {
+            return;
+        }
+        let skipped_columns = visible.top_left.x.abs_diff(area.top_left.x);
+        let skipped_rows = visible.top_left.y.abs_diff(area.top_left.y);
+
+        match direction {
+            GradientDirection::Vertical => {
+                let steps = u64::from(height.saturating_sub(1)).max(1);
+                let color = |row| lerp(start, end, u64::from(skipped_rows + row), steps);
+                for (band, color) in bands(visible.size.height, color) {
+                    let band = Rectangle::new(
+                        visible.top_left + Point::new(0, band.start as i32),
+                        Size::new(visible.size.width, band.len() as u32),
+                    );
+                    self.fill_solid_storage(&band, color);
+                }
+            }
+            GradientDirection::Horizontal => {
+                let steps = u64::from(width.saturating_sub(1)).max(1);
+                let color = |column| lerp(start, end, u64::from(skipped_columns + column), steps);
+                for (band, color) in bands(visible.size.width, color) {
+                    let band = Rectangle::new(
+                        visible.top_left + Point::new(band.start as i32, 0),
+                        Size::new(band.len() as u32, visible.size.height),
+                    );
+                    self.fill_solid_storage(&band, color);
+                }
+            }
+            GradientDirection::Diagonal => {
+                let steps = (u64::from(width) + u64::from(height))
+                    .saturating_sub(2)
+                    .max(1);
+                let skipped = u64::from(skipped_columns) + u64::from(skipped_rows);
+                let Size { width, height } = visible.size;
+                let colors = (0..height).flat_map(|y| {
+                    (0..width).map(move |x| {
+                        lerp(start, end, skipped + u64::from(x) + u64::from(y), steps)
+                    })
+                });
+                self.fill_contiguous_storage(&visible, colors);
+            }
+        }
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Fills each row of `area`, in embedded-graphics coordinates, with a native color.
+    fn fill_rows_storage(&mut self, area: &Rectangle, row_colors: impl IntoIterator<Item = u32>) {
+        let visible = safe_intersection(area, &self.bounding_box());
+        if visible.is_zero_sized() {
+            return;
+        }
+
+        // Colors of rows above the screen are skipped over.
+        let mut row_colors = row_colors.into_iter();
+        let skipped_rows = visible.top_left.y.abs_diff(area.top_left.y) as usize;
+        if skipped_rows > 0 && row_colors.nth(skipped_rows - 1).is_none() {
+            return;
+        }
+
+        for (y, color) in visible.rows().zip(row_colors) {
+            let row = Rectangle::new(
+                Point::new(visible.top_left.x, y),
+                Size::new(visible.size.width, 1),
+            );
+            self.fill_solid_storage(&row, color);
+        }
+    }
+}
+
+/// Interpolates each channel of two native colors, `step` out of `steps` of the way from `from`
+/// to `to`.
+fn lerp(from: u32, to: u32, step: u64, steps: u64) -> u32 {
+    let channel = |shift: u32| {
+        let from = i64::from((from >> shift) & 0xFF);
+        let to = i64::from((to >> shift) & 0xFF);
+        // Both are under 2^33, so they fit an `i64` with room for the product.
+        let value = from + (to - from) * step as i64 / steps as i64;
+        (value as u32) << shift
+    };
+
+    channel(16) | channel(8) | channel(0)
+}
+
+/// Splits `0..len` into runs of positions that `color` gives the same color, along with that
+/// color.
+fn bands(len: u32, color: impl Fn(u32) -> u32) -> impl Iterator<Item = (Range<u32>, u32)> {
+    let mut start = 0;
+    core::iter::from_fn(move || {
+        if start >= len {
+            return None;
+        }
+
+        let band_color = color(start);
+        let end = (start + 1..len)
+            .find(|&position| color(position) != band_color)
+            .unwrap_or(len);
+        let band = start..end;
+        start = end;
+        Some((band, band_color))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::pixelcolor::Rgb888;
+
+    use super::*;
+    use crate::MockPanel;
+
+    #[test]
+    fn gradient_colors_follow_the_whole_area_when_clipped() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        // 100 of the 200 rows and columns are off screen, so the first visible ones are a
+        // bit over halfway along.
+        let area = Rectangle::new(Point::new(-100, -100), Size::new(200, 200));
+
+        display.fill_gradient(
+            &area,
+            Rgb888::BLACK,
+            Rgb888::WHITE,
+            GradientDirection::Vertical,
+        );
+        assert_eq!(display.backend().pixel(0, 0), Some(0x808080));
+        assert_eq!(display.backend().pixel(0, 99), Some(0xFFFFFF));
+
+        display.fill_gradient(
+            &area,
+            Rgb888::BLACK,
+            Rgb888::WHITE,
+            GradientDirection::Horizontal,
+        );
+        assert_eq!(display.backend().pixel(0, 0), Some(0x808080));
+        assert_eq!(display.backend().pixel(99, 0), Some(0xFFFFFF));
+
+        display.fill_gradient(
+            &area,
+            Rgb888::BLACK,
+            Rgb888::WHITE,
+            GradientDirection::Diagonal,
+        );
+        assert_eq!(display.backend().pixel(0, 0), Some(0x808080));
+        assert_eq!(display.backend().pixel(99, 99), Some(0xFFFFFF));
+    }
+
+    #[test]
+    fn gradients_over_huge_areas_only_draw_the_screen() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let area = Rectangle::new(
+            Point::new(i32::MIN, i32::MIN),
+            Size::new(u32::MAX, u32::MAX),
+        );
+
+        for direction in [
+            GradientDirection::Vertical,
+            GradientDirection::Horizontal,
+            GradientDirection::Diagonal,
+        ] {
+            display.fill_gradient(&area, Rgb888::BLACK, Rgb888::WHITE, direction);
+        }
+        // Colors of rows above the screen still have to be skipped one by one, so this area
+        // only reaches far off screen sideways and down.
+        let rows = Rectangle::new(Point::new(i32::MIN, 0), area.size);
+        display.fill_rows(&rows, core::iter::repeat(Rgb888::RED));
+
+        assert!(
+            display
+                .backend()
+                .framebuffer()
+                .iter()
+                .all(|&color| color == 0xFF0000)
+        );
+    }
+
+    #[test]
+    fn fill_rows_skips_the_colors_of_rows_above_the_screen() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let area = Rectangle::new(Point::new(0, -2), Size::new(10, 4));
+
+        display.fill_rows(
+            &area,
+            [Rgb888::RED, Rgb888::GREEN, Rgb888::BLUE, Rgb888::WHITE],
+        );
+
+        assert_eq!(display.backend().pixel(0, 0), Some(0x0000FF));
+        assert_eq!(display.backend().pixel(0, 1), Some(0xFFFFFF));
+        assert_eq!(display.backend().pixel(0, 2), Some(0));
+    }
+
+    #[test]
+    fn vertical_gradients_take_one_fill_per_row() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let area = Rectangle::new(Point::new(5, 5), Size::new(50, 10));
+
+        display.fill_vertical_gradient(&area, Rgb888::BLACK, Rgb888::new(9, 9, 9));
+
+        let panel = display.backend();
+        assert_eq!(panel.fill_rects().len(), 10);
+        for row in 0..10 {
+            assert_eq!(panel.pixel(5, 5 + row), Some(0x010101 * row as u32));
+            assert_eq!(panel.pixel(54, 5 + row), Some(0x010101 * row as u32));
+        }
+    }
+
+    #[test]
+    fn rows_of_the_same_color_share_a_fill() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let area = Rectangle::new(Point::zero(), Size::new(50, 10));
+
+        display.fill_vertical_gradient(&area, Rgb888::RED, Rgb888::RED);
+
+        assert_eq!(display.backend().fill_rects().len(), 1);
+    }
+
+    #[test]
+    fn fill_rows_takes_one_fill_per_row() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let area = Rectangle::new(Point::zero(), Size::new(50, 10));
+
+        display.fill_rows(&area, (0..10).map(|i| Rgb888::new(i, 0, 0)));
+
+        assert_eq!(display.backend().fill_rects().len(), 10);
+        assert_eq!(display.backend().pixel(49, 9), Some(0x090000));
+    }
+}
@@ -0,0 +1,269 @@
+//! Common status icons, drawn with the driver's accelerated fills.
+
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use crate::{DisplayDriver, PanelBackend, ToDisplayStorage};
+
+/// The size and colors of an icon drawn by a method like [`DisplayDriver::draw_battery_icon`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IconStyle<C> {
+    /// Height of the icon in pixels. Icons are scaled to fit, and most are about as wide as they
+    /// are tall; the battery is twice as wide.
+    pub size: u32,
+    /// Color of outlines and of the filled part of the icon.
+    pub foreground: C,
+    /// Color of the unfilled parts of the icon.
+    pub background: C,
+    /// Color of highlights, like the battery's charging bolt or the warning sign's triangle.
+    pub accent: C,
+}
+
+impl<C> IconStyle<C> {
+    /// Creates a new icon style.
+    #[must_use]
+    pub const fn new(size: u32, foreground: C, background: C, accent: C) -> Self {
+        Self {
+            size,
+            foreground,
+            background,
+            accent,
+        }
+    }
+}
+
+/// Native versions of an [`IconStyle`]'s colors, along with the line thickness for its size.
+struct Palette {
+    size: i32,
+    line: i32,
+    foreground: u32,
+    background: u32,
+    accent: u32,
+}
+
+impl Palette {
+    fn new<C: ToDisplayStorage>(style: &IconStyle<C>) -> Self {
+        Self {
+            size: style.size as i32,
+            line: (style.size as i32 / 10).max(1),
+            foreground: style.foreground.to_display_storage(),
+            background: style.background.to_display_storage(),
+            accent: style.accent.to_display_storage(),
+        }
+    }
+}
+
+impl<C: ToDisplayStorage, B: PanelBackend> DisplayDriver<C, B> {
+    /// Draws a battery with its top left corner at `top_left`, filled to `percent`.
+    ///
+    /// The battery is `style.size` pixels tall and twice as wide. Its inside is filled from the
+    /// left in proportion to `percent`, which is clamped to 100, and a bolt is drawn over it while
+    /// `charging`.
+    pub fn draw_battery_icon(
+        &mut self,
+        top_left: Point,
+        percent: u8,
+        charging: bool,
+        style: &IconStyle<C>,
+    ) {
+        let palette = Palette::new(style);
+        let (size, line) = (palette.size, palette.line);
+
+        // Terminal on the right hand side
+        let terminal_width = (size / 6).max(1);
+        let body_width = size * 2 - terminal_width;
+        self.fill_icon_rect(
+            top_left + Point::new(body_width, size / 4),
+            (terminal_width, size - size / 4 * 2),
+            palette.foreground,
+        );
+
+        // Outline and empty inside
+        self.fill_icon_rect(top_left, (body_width, size), palette.foreground);
+        self.fill_icon_rect(
+            top_left + Point::new(line, line),
+            (body_width - line * 2, size - line * 2),
+            palette.background,
+        );
+
+        // Charge level, with a gap between it and the outline
+        let level_left = top_left + Point::new(line * 2, line * 2);
+        let (level_width, level_height) = (body_width - line * 4, size - line * 4);
+        let filled = level_width.max(0) * i32::from(percent.min(100)) / 100;
+        self.fill_icon_rect(level_left, (filled, level_height), palette.foreground);
+
+        if charging {
+            let center = level_left + Point::new(level_width / 2, level_height / 2);
+            let (half_width, bottom) = (level_height / 3, level_left.y + level_height - 1);
+            self.fill_triangle_storage(
+                Point::new(center.x + half_width, level_left.y),
+                Point::new(center.x - half_width, center.y),
+                Point::new(center.x, center.y),
+                palette.accent,
+            );
+            self.fill_triangle_storage(
+                Point::new(center.x, center.y),
+                Point::new(center.x + half_width, center.y),
+                Point::new(center.x - half_width, bottom),
+                palette.accent,
+            );
+        }
+    }
+
+    /// Draws a signal strength indicator with its top left corner at `top_left`.
+    ///
+    /// The indicator is four bars of increasing height, `style.size` pixels square overall. The
+    /// first `bars` of them, clamped to 4, are drawn in the foreground color and the rest in the
+    /// background color. Useful for radio or controller link quality.
+    pub fn draw_signal_icon(&mut self, top_left: Point, bars: u8, style: &IconStyle<C>) {
+        let palette = Palette::new(style);
+        let size = palette.size;
+        let pitch = size / 4;
+        let bar_width = (pitch - palette.line).max(1);
+
+        for bar in 0..4 {
+            let height = size * (bar + 1) / 4;
+            let color = if bar < i32::from(bars) {
+                palette.foreground
+            } else {
+                palette.background
+            };
+            self.fill_icon_rect(
+                top_left + Point::new(bar * pitch, size - height),
+                (bar_width, height),
+                color,
+            );
+        }
+    }
+
+    /// Draws a warning sign, an exclamation mark in a triangle, with its top left corner at
+    /// `top_left`.
+    ///
+    /// The triangle is `style.size` pixels square and drawn in the accent color, with the
+    /// exclamation mark in the foreground color.
+    pub fn draw_warning_icon(&mut self, top_left: Point, style: &IconStyle<C>) {
+        let palette = Palette::new(style);
+        let size = palette.size;
+        let center = top_left.x + size / 2;
+
+        self.fill_triangle_storage(
+            Point::new(center, top_left.y),
+            Point::new(top_left.x, top_left.y + size - 1),
+            Point::new(top_left.x + size - 1, top_left.y + size - 1),
+            palette.accent,
+        );
+
+        let mark_width = (size / 8).max(1);
+        let mark_left = center - mark_width / 2;
+        self.fill_icon_rect(
+            Point::new(mark_left, top_left.y + size * 3 / 8),
+            (mark_width, size * 3 / 10),
+            palette.foreground,
+        );
+        self.fill_icon_rect(
+            Point::new(mark_left, top_left.y + size * 3 / 4),
+            (mark_width, mark_width),
+            palette.foreground,
+        );
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Fills a rectangle given by its top left corner and a size that may be negative, in which
+    /// case nothing is drawn.
+    fn fill_icon_rect(&mut self, top_left: Point, (width, height): (i32, i32), color: u32) {
+        if width > 0 && height > 0 {
+            let area = Rectangle::new(top_left, Size::new(width as u32, height as u32));
+            self.fill_solid_storage(&area, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::pixelcolor::Rgb888;
+
+    use super::*;
+    use crate::MockPanel;
+
+    const STYLE: IconStyle<Rgb888> =
+        IconStyle::new(24, Rgb888::WHITE, Rgb888::BLUE, Rgb888::YELLOW);
+
+    /// Returns the length of the run of `color` starting at `(x, y)`.
+    fn run_length(panel: &MockPanel, x: i32, y: i32, color: u32) -> i32 {
+        (x..)
+            .take_while(|&x| panel.pixel(x, y) == Some(color))
+            .count() as i32
+    }
+
+    #[test]
+    fn half_charged_battery_fills_half_its_body() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        display.draw_battery_icon(Point::zero(), 50, false, &STYLE);
+
+        // A 24 pixel battery has a 2 pixel outline, a 2 pixel gap inside it, and a 4 pixel
+        // terminal, which leaves 36 pixels of body for the charge level.
+        let panel = display.backend();
+        assert_eq!(run_length(panel, 4, 12, 0xFFFFFF), 18);
+        assert_eq!(run_length(panel, 22, 12, 0x0000FF), 18 + 2);
+        assert_eq!(run_length(panel, 42, 12, 0xFFFFFF), 2 + 4);
+    }
+
+    #[test]
+    fn battery_levels_are_clamped() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        display.draw_battery_icon(Point::zero(), 0, false, &STYLE);
+        assert_eq!(run_length(display.backend(), 2, 12, 0x0000FF), 40);
+
+        display.draw_battery_icon(Point::zero(), 255, false, &STYLE);
+        assert_eq!(run_length(display.backend(), 4, 12, 0xFFFFFF), 36);
+    }
+
+    #[test]
+    fn charging_batteries_get_a_bolt() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        display.draw_battery_icon(Point::new(10, 10), 0, true, &STYLE);
+
+        let panel = display.backend();
+        let bolt = panel
+            .framebuffer()
+            .iter()
+            .filter(|&&c| c == 0xFFFF00)
+            .count();
+        assert!(bolt > 0);
+        assert_eq!(panel.pixel(10 + 4 + 18, 10 + 12), Some(0xFFFF00));
+    }
+
+    #[test]
+    fn signal_bars_are_filled_up_to_the_count() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        display.draw_signal_icon(Point::zero(), 2, &STYLE);
+
+        // Each bar is 6 pixels apart and the bottom row is covered by all of them.
+        let panel = display.backend();
+        assert_eq!(panel.pixel(0, 23), Some(0xFFFFFF));
+        assert_eq!(panel.pixel(6, 23), Some(0xFFFFFF));
+        assert_eq!(panel.pixel(12, 23), Some(0x0000FF));
+        assert_eq!(panel.pixel(18, 0), Some(0x0000FF));
+        assert_eq!(panel.pixel(6, 11), Some(0));
+        assert_eq!(panel.pixel(6, 12), Some(0xFFFFFF));
+    }
+
+    #[test]
+    fn warning_sign_has_a_mark_inside_its_triangle() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        display.draw_warning_icon(Point::zero(), &STYLE);
+
+        let panel = display.backend();
+        assert_eq!(panel.pixel(12, 0), Some(0xFFFF00));
+        assert_eq!(panel.pixel(0, 23), Some(0xFFFF00));
+        assert_eq!(panel.pixel(23, 23), Some(0xFFFF00));
+        assert_eq!(panel.pixel(12, 10), Some(0xFFFFFF));
+        assert_eq!(panel.pixel(12, 19), Some(0xFFFFFF));
+        assert_eq!(panel.pixel(0, 0), Some(0));
+    }
+}
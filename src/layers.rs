@@ -0,0 +1,357 @@
+//! Offscreen layers that are composited over the display when it is rendered.
+
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use crate::{
+    DisplayDriver, DisplayError, PanelBackend, ToDisplayStorage, WIDTH, dirty::DirtyRegion,
+    frame::safe_intersection, skip,
+};
+
+/// The value of a transparent layer pixel, which no `0x00RRGGBB` color can have.
+const TRANSPARENT: u32 = u32::MAX;
+
+/// Identifies a layer added with [`DisplayDriver::add_layer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayerId(usize);
+
+/// A screen-sized buffer of pixels drawn over the display.
+#[derive(Debug, Clone)]
+pub(crate) struct LayerBuffer {
+    pixels: Box<[u32]>,
+    changed: DirtyRegion,
+}
+
+impl LayerBuffer {
+    fn new(len: usize) -> Self {
+        Self {
+            pixels: vec![TRANSPARENT; len].into_boxed_slice(),
+            changed: DirtyRegion::default(),
+        }
+    }
+}
+
+/// A draw target for one of the driver's layers.
+///
+/// Created with [`DisplayDriver::layer`].
+pub struct Layer<'a, C, B: PanelBackend> {
+    driver: &'a mut DisplayDriver<C, B>,
+    index: usize,
+}
+
+impl<C, B> DisplayDriver<C, B> {
+    /// Resizes every layer to the screen after the header offset changed, which clears them.
+    pub(crate) fn reset_layers(&mut self) {
+        let len = WIDTH as usize * self.screen_height() as usize;
+        for layer in &mut self.layers {
+            *layer = LayerBuffer::new(len);
+        }
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Adds a layer on top of all existing ones, and returns its id.
+    ///
+    /// Layers are for things like a HUD that is drawn over a background: each is a separate,
+    /// screen-sized buffer that [`layer`](Self::layer) draws into, and that is composited over
+    /// the display on every [`render`](Self::render). The display itself is the bottom layer, and
+    /// layers are stacked in the order they were added, so the last one added is on top.
+    ///
+    /// A layer starts out fully transparent, and drawing to it makes pixels opaque. There is no
+    /// partial transparency: a pixel either shows the layer's color or whatever is below it. Use
+    /// [`Layer::erase`] to make pixels transparent again.
+    ///
+    /// # Memory
+    ///
+    /// Each layer costs one `u32` per pixel (about 460 KB), and layers need the
+    /// [shadow buffer](Self::enable_shadow_buffer) to know what is below them, so the first layer
+    /// enables it. Since the shadow buffer only knows about what is drawn after it is enabled, add
+    /// layers before drawing anything else. Drivers without layers don't pay for either.
+    pub fn add_layer(&mut self) -> LayerId {
+        self.enable_shadow_buffer();
+        let len = WIDTH as usize * self.screen_height() as usize;
+        self.layers.push(LayerBuffer::new(len));
+        LayerId(self.layers.len() - 1)
+    }
+
+    /// Returns a draw target for the layer `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is from another driver and this one has fewer layers.
+    pub fn layer(&mut self, id: LayerId) -> Layer<'_, C, B> {
+        assert!(id.0 < self.layers.len(), "no layer with id {}", id.0);
+        Layer {
+            driver: self,
+            index: id.0,
+        }
+    }
+
+    /// Draws the layers over everything that changed since the last render, either on the
+    /// display or in a layer.
+    pub(crate) fn composite_layers(&mut self) {
+        if let Some(area) = self.take_composite_area() {
+            self.composite_area(&area);
+        }
+    }
+
+    /// Returns the area that layers need to be composited over, or `None` if there are no layers
+    /// or nothing changed, and marks the layers as composited.
+    pub(crate) fn take_composite_area(&mut self) -> Option<Rectangle> {
+        if self.layers.is_empty() {
+            return None;
+        }
+
+        let mut area = DirtyRegion::default();
+        if let Some(dirty) = self.dirty.area() {
+            area.include(&dirty);
+        }
+        for layer in &mut self.layers {
+            if let Some(changed) = layer.changed.area() {
+                area.include(&changed);
+            }
+            layer.changed.clear();
+        }
+
+        area.area()
+    }
+
+    /// Composites the layers over a non-empty area of the screen.
+    pub(crate) fn composite_area(&mut self, area: &Rectangle) {
+        let width = area.size.width as usize;
+        for y in area.rows() {
+            let Some(shadow) = &self.shadow else {
+                return;
+            };
+            let start = y as usize * WIDTH as usize + area.top_left.x as usize;
+            self.buffer[..width].copy_from_slice(&shadow[start..][..width]);
+            for layer in &self.layers {
+                for (pixel, &color) in self.buffer[..width]
+                    .iter_mut()
+                    .zip(&layer.pixels[start..][..width])
+                {
+                    if color != TRANSPARENT {
+                        *pixel = color;
+                    }
+                }
+            }
+
+            let row = Rectangle::new(
+                Point::new(area.top_left.x, y),
+                Size::new(area.size.width, 1),
+            );
+            self.copy_span_to_panel(&row, 0);
+        }
+    }
+}
+
+impl<C, B: PanelBackend> Layer<'_, C, B> {
+    /// Makes the part of the layer inside `area`, in embedded-graphics coordinates, transparent
+    /// again.
+    pub fn erase(&mut self, area: &Rectangle) {
+        self.fill_storage(area, TRANSPARENT);
+    }
+
+    fn set_pixel(&mut self, point: Point, color: u32) {
+        let Some(point) = self.driver.to_screen(point) else {
+            return;
+        };
+
+        let layer = &mut self.driver.layers[self.index];
+        layer.pixels[point.y as usize * WIDTH as usize + point.x as usize] = color;
+        layer
+            .changed
+            .include(&Rectangle::new(point, Size::new(1, 1)));
+    }
+
+    /// Writes colors to the visible row of `width` pixels starting at `start`, in
+    /// embedded-graphics coordinates, and returns `false` if they ran out first.
+    ///
+    /// The pixels that did get a color are written either way.
+    fn write_row(
+        &mut self,
+        start: Point,
+        width: usize,
+        colors: &mut impl Iterator<Item = u32>,
+    ) -> bool {
+        let Some(first) = self.driver.to_screen(start) else {
+            return false;
+        };
+        // A row of the layer's own coordinates can run in any direction on screen, depending on
+        // the rotation and mirroring, so find out how far apart its pixels are in the buffer.
+        let step = match self.driver.to_screen(start + Point::new(1, 0)) {
+            Some(next) if width > 1 => {
+                (next.y - first.y) as isize * WIDTH as isize + (next.x - first.x) as isize
+            }
+            _ => 1,
+        };
+        let base = first.y as usize * WIDTH as usize + first.x as usize;
+
+        let pixels = &mut self.driver.layers[self.index].pixels;
+        let mut written = 0;
+        if step == 1 {
+            for (pixel, color) in pixels[base..][..width].iter_mut().zip(colors) {
+                *pixel = color;
+                written += 1;
+            }
+        } else {
+            for (offset, color) in (0..width as isize).zip(colors) {
+                pixels[base.wrapping_add_signed(offset * step)] = color;
+                written += 1;
+            }
+        }
+
+        if written > 0 {
+            let row = self
+                .driver
+                .screen_rect(&Rectangle::new(start, Size::new(written as u32, 1)));
+            self.driver.layers[self.index].changed.include(&row);
+        }
+        written == width
+    }
+
+    fn fill_storage(&mut self, area: &Rectangle, color: u32) {
+        let visible = self.driver.clip_to_screen(area);
+        let layer = &mut self.driver.layers[self.index];
+        for y in visible.rows() {
+            let start = y as usize * WIDTH as usize + visible.top_left.x as usize;
+            layer.pixels[start..][..visible.size.width as usize].fill(color);
+        }
+        layer.changed.include(&visible);
+    }
+}
+
+impl<C, B: PanelBackend> OriginDimensions for Layer<'_, C, B> {
+    fn size(&self) -> Size {
+        self.driver.size()
+    }
+}
+
+impl<C: ToDisplayStorage, B: PanelBackend> DrawTarget for Layer<'_, C, B> {
+    type Color = C;
+
+    type Error = DisplayError;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.set_pixel(point, color.to_display_storage());
+        }
+
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let visible = safe_intersection(area, &self.driver.bounding_box());
+        if visible.is_zero_sized() {
+            return Ok(());
+        }
+
+        // Colors outside the layer are skipped over like the driver's own `fill_contiguous`
+        // does, which also stops after the last visible row.
+        let width = visible.size.width as usize;
+        let skip_left = visible.top_left.x.abs_diff(area.top_left.x) as usize;
+        let skip_right = area.size.width as usize - skip_left - width;
+        let skip_above = (visible.top_left.y.abs_diff(area.top_left.y) as usize)
+            .saturating_mul(area.size.width as usize);
+        let mut colors = colors.into_iter().map(C::to_display_storage);
+        if !skip(&mut colors, skip_above) {
+            return Err(DisplayError::ColorBufferTooSmall);
+        }
+
+        for y in visible.rows() {
+            let start = Point::new(visible.top_left.x, y);
+            if !skip(&mut colors, skip_left)
+                || !self.write_row(start, width, &mut colors)
+                || !skip(&mut colors, skip_right)
+            {
+                return Err(DisplayError::ColorBufferTooSmall);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.fill_storage(area, color.to_display_storage());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::pixelcolor::{Rgb888, raw::RawU24};
+
+    use super::*;
+    use crate::{MockPanel, Rotation};
+
+    /// A distinct color for each of the first 2^24 pixels.
+    fn numbered() -> impl Iterator<Item = Rgb888> {
+        (0..).map(|i| Rgb888::from(RawU24::new(i)))
+    }
+
+    #[test]
+    fn fill_contiguous_matches_the_driver_under_every_rotation() {
+        let area = Rectangle::new(Point::new(-7, -5), Size::new(30, 20));
+        for rotation in [
+            Rotation::Deg0,
+            Rotation::Deg90,
+            Rotation::Deg180,
+            Rotation::Deg270,
+        ] {
+            let mut direct = DisplayDriver::new(MockPanel::new());
+            direct.set_rotation(rotation);
+            direct.fill_contiguous(&area, numbered().take(600)).unwrap();
+
+            let mut layered = DisplayDriver::new(MockPanel::new());
+            layered.set_rotation(rotation);
+            let id = layered.add_layer();
+            layered
+                .layer(id)
+                .fill_contiguous(&area, numbered().take(600))
+                .unwrap();
+            layered.render();
+
+            assert!(
+                direct.backend().framebuffer() == layered.backend().framebuffer(),
+                "layer differs under {rotation:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn fill_contiguous_draws_what_it_got_colors_for() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let id = display.add_layer();
+        let area = Rectangle::new(Point::new(0, 0), Size::new(10, 2));
+
+        let result = display
+            .layer(id)
+            .fill_contiguous(&area, core::iter::repeat_n(Rgb888::RED, 13));
+        display.render();
+
+        assert_eq!(result, Err(DisplayError::ColorBufferTooSmall));
+        let panel = display.backend();
+        assert_eq!(panel.pixel(9, 0), Some(0xFF0000));
+        assert_eq!(panel.pixel(2, 1), Some(0xFF0000));
+        assert_eq!(panel.pixel(3, 1), Some(0));
+    }
+
+    #[test]
+    fn fill_contiguous_over_a_huge_area_is_clipped() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let id = display.add_layer();
+        let area = Rectangle::new(Point::new(i32::MIN, 0), Size::new(u32::MAX, u32::MAX));
+
+        let result = display
+            .layer(id)
+            .fill_contiguous(&area, core::iter::repeat_n(Rgb888::RED, 1000));
+
+        assert_eq!(result, Err(DisplayError::ColorBufferTooSmall));
+    }
+}
@@ -54,43 +54,424 @@
 //!
 //! [`embedded-graphics` docs]: https://docs.rs/embedded-graphics/latest/embedded_graphics/examples/index.html
 
-use core::convert::Infallible;
-use embedded_graphics_core::{pixelcolor::Rgb888, prelude::*};
-use vex_sdk::{vexDisplayCopyRect, vexDisplayForegroundColor, vexDisplayRectFill};
-use vexide::display::{Display, RenderMode, TouchEvent};
+use core::marker::PhantomData;
+use embedded_graphics::primitives::{Circle, ContainsPoint};
+use embedded_graphics_core::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+use std::time::Instant;
+use vexide::display::Display;
+
+use crate::{
+    dirty::DirtyRegion, frame::safe_intersection, layers::LayerBuffer, managed::ManagedRegion,
+};
+
+mod alpha;
+mod auto_render;
+mod backend;
+mod background;
+mod binary;
+mod blit;
+mod bmp;
+mod builtin_text;
+mod canvas;
+mod checked;
+mod clip;
+mod color;
+pub mod color_math;
+mod controller;
+mod coverage;
+mod dashboard;
+mod dirty;
+mod error;
+mod flush;
+mod frame;
+mod gesture;
+mod gradient;
+mod icons;
+mod layers;
+mod managed;
+#[cfg(any(test, feature = "mock"))]
+mod mock;
+mod orientation;
+mod output;
+mod pacing;
+mod palette;
+mod restore;
+mod scrolling;
+mod shapes;
+mod sprite;
+#[cfg(feature = "stats")]
+mod stats;
+mod surface;
+mod text;
+mod touch;
+mod viewport;
+mod yielding;
+
+pub use alpha::{Blended, Rgba8888};
+pub use auto_render::AutoRender;
+pub use backend::{PanelBackend, RenderMode, TouchEvent, TouchState};
+pub use binary::BinaryDrawTarget;
+pub use blit::NinePatch;
+pub use canvas::OffscreenCanvas;
+pub use clip::ClippedTarget;
+pub use color::{ToDisplayStorage, to_storage};
+pub use controller::ControllerDisplayDriver;
+pub use coverage::CoverageDrawTarget;
+pub use dashboard::{Dashboard, Widget, WidgetId, WidgetStyle};
+pub use error::DisplayError;
+pub use flush::FlushMethod;
+pub use frame::Insets;
+pub use gesture::{Gesture, GestureDetector};
+pub use gradient::GradientDirection;
+pub use icons::IconStyle;
+pub use layers::{Layer, LayerId};
+#[cfg(any(test, feature = "mock"))]
+pub use mock::MockPanel;
+pub use orientation::Rotation;
+pub use output::StorageOrder;
+pub use pacing::{Clock, FramePacer, SystemClock};
+pub use palette::PaletteDrawTarget;
+pub use restore::RestoreRegion;
+pub use scrolling::ScrollingText;
+pub use sprite::SpriteSheet;
+#[cfg(feature = "stats")]
+pub use stats::{DrawStats, FrameStats, OverlayCorner};
+pub use surface::DrawSurface;
+pub use text::measure_text;
+pub use touch::TouchPoint;
+pub use viewport::Viewport;
+
+/// Width of the writable part of the display.
+const WIDTH: u32 = Display::HORIZONTAL_RESOLUTION as _;
+
+/// Height of the writable part of the display.
+const HEIGHT: u32 = Display::VERTICAL_RESOLUTION as _;
+
+/// Number of rows the firmware normally reserves for its header.
+const HEADER_HEIGHT: u32 = Display::HEADER_HEIGHT as _;
+
+/// Height of the whole panel, including the header.
+const PANEL_HEIGHT: u32 = HEIGHT + HEADER_HEIGHT;
 
 /// An embedded-graphics draw target for the V5 Brain display
-/// Currently, this does not support touch detection like the regular [`Display`] API.
-pub struct DisplayDriver {
-    display: Display,
-    buffer: [u32; Display::HORIZONTAL_RESOLUTION as usize * Display::VERTICAL_RESOLUTION as usize],
+///
+/// The driver draws with colors of type `C`, which is [`Rgb888`] unless another
+/// [`ToDisplayStorage`] color is picked with [`with_color_type`](Self::with_color_type). Drawing
+/// goes through a [`PanelBackend`], which is the [`Display`] peripheral unless you're testing
+/// against something like the `MockPanel` from the `mock` feature.
+///
+/// Like other embedded-graphics targets, the [`DrawTarget`] methods silently clip anything outside
+/// of the display. The only error they report is [`DisplayError::ColorBufferTooSmall`], when
+/// [`fill_contiguous`](DrawTarget::fill_contiguous) is given fewer colors than the visible part
+/// of its area needs, after drawing the pixels that it did get colors for, in order, and leaving
+/// the rest as they were. Strict callers can use the `try_` methods, like
+/// [`try_fill_solid`](Self::try_fill_solid), to get an error for off-screen or malformed areas
+/// too.
+///
+/// [`fill_contiguous`](DrawTarget::fill_contiguous) copies its area in bands that fit the driver's
+/// scratch buffer, so any area can be filled, up to the whole screen, however large the buffer.
+///
+/// ```
+/// use embedded_graphics_core::{
+///     pixelcolor::{Rgb888, raw::RawU24},
+///     prelude::*,
+/// };
+/// use vexide_embedded_graphics::{DisplayDriver, MockPanel};
+///
+/// let mut display = DisplayDriver::new(MockPanel::new());
+/// let area = display.bounding_box();
+/// let colors = (0..area.size.width * area.size.height).map(|i| Rgb888::from(RawU24::new(i)));
+/// display.fill_contiguous(&area, colors).unwrap();
+///
+/// // Every pixel of the screen got its own color, in order.
+/// assert!(
+///     display
+///         .backend()
+///         .framebuffer()
+///         .iter()
+///         .enumerate()
+///         .all(|(i, &color)| color == i as u32)
+/// );
+/// ```
+///
+/// The [`DrawTargetExt`](embedded_graphics::draw_target::DrawTargetExt) adapters, like
+/// [`clipped`](embedded_graphics::draw_target::DrawTargetExt::clipped),
+/// [`translated`](embedded_graphics::draw_target::DrawTargetExt::translated) and
+/// [`cropped`](embedded_graphics::draw_target::DrawTargetExt::cropped), pass
+/// [`fill_solid`](DrawTarget::fill_solid) and [`fill_contiguous`](DrawTarget::fill_contiguous)
+/// through to the driver after adjusting the area, so drawing through them still uses the
+/// accelerated paths. A full screen fill through `clipped` is one solid fill of the clip area.
+pub struct DisplayDriver<C = Rgb888, B = Display> {
+    display: B,
+    buffer: [u32; WIDTH as usize],
+    shadow: Option<Box<[u32]>>,
+    header_offset: u32,
+    dirty: DirtyRegion,
+    managed: Option<ManagedRegion>,
+    layers: Vec<LayerBuffer>,
+    storage_order: StorageOrder,
+    brightness: u8,
+    gamma: f32,
+    gamma_lut: Option<[u8; 256]>,
+    background: u32,
+    foreground: Option<u32>,
+    binary_colors: (u32, u32),
+    aa_colors: (u32, u32),
+    pixel_aspect: (u32, u32),
+    touch_down: bool,
+    press_count: i32,
+    touch_callback: Option<Box<dyn FnMut(TouchEvent)>>,
+    polled_touch: TouchState,
+    last_render: Option<Instant>,
+    #[cfg(feature = "stats")]
+    stats: stats::StatsState,
+    origin: Point,
+    insets: Insets,
+    rotation: Rotation,
+    mirror: (bool, bool),
+    _color: PhantomData<C>,
 }
 
-impl DisplayDriver {
+impl<B: PanelBackend> DisplayDriver<Rgb888, B> {
     /// Create a new [`DisplayDriver`] from a [`Display`].
     ///
     /// The display peripheral must be moved into this struct,
     /// as it is used to render the display and having multiple
     /// mutable references to it is unsafe.
     #[must_use]
-    pub fn new(display: Display) -> Self {
+    pub fn new(display: B) -> Self {
+        Self::with_color_type(display)
+    }
+
+    /// Create a new [`DisplayDriver`] that can draw to the whole panel, including the rows
+    /// normally left to the firmware's header.
+    ///
+    /// This is [`new`](Self::new) with a [header offset](Self::with_header_offset) of zero, so
+    /// `(0, 0)` is the top left corner of the panel and the driver's [`size`](OriginDimensions)
+    /// is the panel's full 480x272. VEXos still owns the top rows, though, and may draw its header
+    /// over them: the SDK has no call to hide or disable the header, so this is as close to a
+    /// full-screen, kiosk-style display as a program can get.
+    #[must_use]
+    pub fn new_fullscreen(display: B) -> Self {
+        Self::new(display).with_header_offset(0)
+    }
+
+    /// Create a new [`DisplayDriver`] that starts out in the given rendering mode.
+    ///
+    /// This is [`new`](Self::new) followed by [`set_render_mode`](Self::set_render_mode), for the
+    /// common case of a driver that is double buffered from its very first draw.
+    #[must_use]
+    pub fn with_render_mode(display: B, mode: RenderMode) -> Self {
+        let mut driver = Self::new(display);
+        driver.set_render_mode(mode);
+        driver
+    }
+}
+
+impl<C: ToDisplayStorage, B: PanelBackend> DisplayDriver<C, B> {
+    /// Create a new [`DisplayDriver`] that draws with colors of type `C`.
+    ///
+    /// This is the same as [`new`](DisplayDriver::new), but for drawing with a color type other
+    /// than [`Rgb888`]:
+    ///
+    /// ```
+    /// # use vexide::prelude::*;
+    /// # use vexide_embedded_graphics::DisplayDriver;
+    /// use embedded_graphics_core::pixelcolor::Rgb565;
+    ///
+    /// # #[vexide::main]
+    /// # async fn main(peripherals: Peripherals) {
+    /// let mut display = DisplayDriver::<Rgb565>::with_color_type(peripherals.display);
+    /// # }
+    /// ```
+    ///
+    /// # Memory
+    ///
+    /// The SDK only accepts 32-bit colors, so the driver's own scratch buffer and the optional
+    /// [shadow buffer](Self::enable_shadow_buffer) hold 32-bit pixels whatever `C` is, and each
+    /// color is converted as it is drawn. The scratch buffer only holds a single row (under 2 KB),
+    /// since images are copied to the display a row at a time, so the shadow buffer is the only
+    /// large allocation. A smaller color type like [`Rgb565`](embedded_graphics_core::pixelcolor::Rgb565)
+    /// still halves the memory taken by images and other assets stored in that format, at the cost
+    /// of a conversion per pixel when they are drawn.
+    ///
+    /// The scratch row is small enough to live inside the driver, so there is no buffer to hand
+    /// in when creating one. The shadow buffer is allocated when it is
+    /// [enabled](Self::enable_shadow_buffer) and freed when it is
+    /// [disabled](Self::disable_shadow_buffer), so a driver that doesn't need it never pays for
+    /// it.
+    #[must_use]
+    pub fn with_color_type(display: B) -> Self {
+        let press_count = display.touch_status().press_count;
         Self {
             display,
-            #[allow(clippy::large_stack_arrays)] // we got plenty
-            buffer: [0; Display::HORIZONTAL_RESOLUTION as usize
-                * Display::VERTICAL_RESOLUTION as usize],
+            buffer: [0; WIDTH as usize],
+            shadow: None,
+            header_offset: Self::STATUS_BAR_HEIGHT,
+            dirty: DirtyRegion::default(),
+            managed: None,
+            layers: Vec::new(),
+            storage_order: StorageOrder::Rgb,
+            brightness: u8::MAX,
+            gamma: 1.0,
+            gamma_lut: None,
+            background: 0,
+            foreground: None,
+            binary_colors: (0xFF_FFFF, 0),
+            aa_colors: (0xFF_FFFF, 0),
+            pixel_aspect: (1, 1),
+            touch_down: false,
+            press_count,
+            touch_callback: None,
+            polled_touch: TouchState::Released,
+            last_render: None,
+            #[cfg(feature = "stats")]
+            stats: stats::StatsState::default(),
+            origin: Point::zero(),
+            insets: Insets::ZERO,
+            rotation: Rotation::Deg0,
+            mirror: (false, false),
+            _color: PhantomData,
         }
     }
 
+    /// Create a new [`DisplayDriver`] and clear the whole display to `color`.
+    ///
+    /// Whatever VEXos last drew stays on the panel until it's drawn over, so this is an easy way
+    /// to make sure the first frame starts from a solid background. `color` also becomes the
+    /// [background color](Self::set_background_color).
+    #[must_use]
+    pub fn with_clear(display: B, color: C) -> Self {
+        let mut driver = Self::with_color_type(display);
+        driver.set_background_color(color);
+        driver.write_rect(&driver.screen_bounds(), driver.background);
+        driver
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Returns a reference to the backend this driver draws to.
+    #[must_use]
+    pub const fn backend(&self) -> &B {
+        &self.display
+    }
+
+    /// Returns a mutable reference to the backend this driver draws to.
+    pub const fn backend_mut(&mut self) -> &mut B {
+        // The backend's foreground color may be changed through this, so it's set again on the
+        // next draw.
+        self.foreground = None;
+        &mut self.display
+    }
+
     /// Returns the current touch status of the display.
     #[must_use]
     pub fn touch_status(&self) -> TouchEvent {
-        self.display.touch_status()
+        let mut touch = self.display.touch_status();
+        touch.point.y -= self.sdk_y(0);
+        touch
+    }
+
+    /// Returns where the screen was just touched, or `None` if it wasn't.
+    ///
+    /// A touch is only returned once: on the first call that sees the screen pressed after it was
+    /// released. Holding a finger on the screen returns `None` until it is lifted and pressed
+    /// again, so this can be polled every frame to detect taps. The point is in embedded-graphics
+    /// coordinates, taking the [origin](Self::set_origin), [insets](Self::set_insets),
+    /// [rotation](Self::set_rotation) and [mirroring](Self::set_mirror) into account, so it can be
+    /// tested against a drawable's `bounding_box()` directly. Touches outside of the drawable
+    /// area are still returned, with coordinates outside of its bounds.
+    pub fn take_touch(&mut self) -> Option<Point> {
+        let touch = self.touch_status();
+        let was_down = core::mem::replace(
+            &mut self.touch_down,
+            matches!(touch.state, TouchState::Pressed | TouchState::Held),
+        );
+        if was_down || !self.touch_down {
+            return None;
+        }
+
+        Some(self.to_logical(touch.point))
+    }
+
+    /// Returns the number of times the screen has been pressed since the brain started, as
+    /// counted by VEXos.
+    ///
+    /// VEXos counts presses in the background, so unlike [`touch_status`](Self::touch_status)'s
+    /// state this doesn't miss taps that start and end between two polls. Comparing it across
+    /// frames tells how many taps happened in between, for example to detect double taps.
+    #[must_use]
+    pub fn touch_press_count(&self) -> u32 {
+        self.touch_status().press_count.try_into().unwrap_or(0)
+    }
+
+    /// Returns `true` if the screen was pressed since the last call, or `false` if it wasn't or
+    /// is only still being held from an earlier press.
+    ///
+    /// New presses are detected from the [press count](Self::touch_press_count), so a quick tap
+    /// is reported even if the screen is already released again by the time this is called.
+    /// Several taps between two calls are reported as a single new press; poll once per frame to
+    /// keep up with a user's tapping, and compare press counts to tell how many there were.
+    pub fn is_new_press(&mut self) -> bool {
+        let count = self.touch_status().press_count;
+        core::mem::replace(&mut self.press_count, count) != count
+    }
+
+    /// Returns `true` if the screen is being touched inside `area`, which is in embedded-graphics
+    /// coordinates.
+    ///
+    /// Unlike [`take_touch`](Self::take_touch), this is `true` for as long as the touch is held,
+    /// and doesn't consume it, so every button on screen can be tested against the same touch.
+    #[must_use]
+    pub fn touched_within(&self, area: &Rectangle) -> bool {
+        self.touch_point().is_some_and(|point| {
+            !safe_intersection(area, &Rectangle::new(point, Size::new(1, 1))).is_zero_sized()
+        })
+    }
+
+    /// Returns `true` if the screen is being touched inside `circle`, which is in
+    /// embedded-graphics coordinates.
+    ///
+    /// See [`touched_within`](Self::touched_within).
+    #[must_use]
+    pub fn touched_within_circle(&self, circle: &Circle) -> bool {
+        self.touch_point()
+            .is_some_and(|point| circle.contains(point))
+    }
+
+    /// Returns where the screen is being touched in embedded-graphics coordinates, or `None` if
+    /// it isn't.
+    pub(crate) fn touch_point(&self) -> Option<Point> {
+        let touch = self.touch_status();
+        matches!(touch.state, TouchState::Pressed | TouchState::Held)
+            .then(|| self.to_logical(touch.point))
     }
 
     /// Sets the rendering mode of the display
+    ///
+    /// Switching modes in the middle of a frame doesn't lose anything that was already drawn:
+    ///
+    /// - Switching to [`RenderMode::Immediate`] [renders](Self::render) first, so anything still
+    ///   waiting in the back buffer is shown before the back buffer is disabled.
+    /// - Switching to [`RenderMode::DoubleBuffered`] starts drawing into a back buffer that
+    ///   doesn't hold what's currently on screen. If the [shadow buffer](Self::enable_shadow_buffer)
+    ///   is enabled, it is copied into the back buffer so the next render shows the current
+    ///   content plus whatever is drawn on top of it. Without the shadow buffer, the first frame
+    ///   after switching should redraw the whole screen.
+    ///
+    /// Setting the mode that is already active does nothing.
     pub fn set_render_mode(&mut self, mode: RenderMode) {
-        self.display.set_render_mode(mode);
+        match (self.display.render_mode(), mode) {
+            (RenderMode::DoubleBuffered, RenderMode::Immediate) => {
+                self.render();
+                self.display.set_render_mode(mode);
+            }
+            (RenderMode::Immediate, RenderMode::DoubleBuffered) => {
+                self.display.set_render_mode(mode);
+                self.restore_from_shadow();
+            }
+            _ => {}
+        }
     }
 
     /// Returns the current rendering mode of the display
@@ -99,95 +480,879 @@ impl DisplayDriver {
         self.display.render_mode()
     }
 
+    /// Returns `true` if the display is in [`RenderMode::DoubleBuffered`], so that draws only
+    /// show up once the display is [rendered](Self::render).
+    #[must_use]
+    pub fn is_double_buffered(&self) -> bool {
+        self.render_mode() == RenderMode::DoubleBuffered
+    }
+
     /// Renders the display if the rendering mode is set to [`RenderMode::DoubleBuffered`].
+    ///
+    /// Any draws waiting in the [managed region](Self::set_managed_region) are copied to the
+    /// display first, and then any [layers](Self::add_layer) are composited over what changed.
+    ///
+    /// The SDK always presents the whole back buffer, so there's no cheaper render for a frame
+    /// that only changed a small area. What can be skipped is rendering frames where nothing
+    /// changed: the driver tracks the [area drawn to](Self::dirty_area) since the last render,
+    /// and [`render_if_dirty`](Self::render_if_dirty) only renders when it isn't empty. To show
+    /// just part of a frame in [`RenderMode::Immediate`], draw it in a managed region and use
+    /// [`render_region`](Self::render_region). See [`flush_method`](Self::flush_method) for how
+    /// the frame gets to the panel.
     pub fn render(&mut self) {
+        self.finish_frame();
         self.display.render();
+        self.last_render = Some(Instant::now());
+        self.dirty.clear();
+    }
+
+    /// Renders the display and waits until the frame is actually visible on the panel.
+    ///
+    /// [`render`](Self::render) may return before the new frame has been picked up by the panel,
+    /// which matters when you need to show something before starting a blocking operation, or
+    /// before capturing the screen from a test harness. In [`RenderMode::DoubleBuffered`] this
+    /// asks `vexDisplayRender` to wait for the display's vertical sync, which is the only
+    /// completion signal the SDK has. In [`RenderMode::Immediate`], where there's no signal at
+    /// all, it sleeps for one full [refresh interval](Display::REFRESH_INTERVAL) instead, so
+    /// other tasks keep running. Either way, expect it to take up to one refresh interval (about
+    /// 16.7 ms), which in [`RenderMode::DoubleBuffered`] is spent blocked in the SDK.
+    pub async fn flush_and_wait(&mut self) {
+        self.finish_frame();
+        self.display.render_and_wait().await;
+        self.last_render = Some(Instant::now());
+        self.dirty.clear();
     }
+
+    /// Puts the display back into its default state and clears it to black.
+    ///
+    /// This undoes everything a program or competition phase may have changed about how drawing
+    /// shows up, so that the next one starts from a known baseline:
+    ///
+    /// - the render mode goes back to [`RenderMode::Immediate`], rendering anything still in the
+    ///   back buffer first,
+    /// - the [brightness](Self::set_brightness) goes back to full and the
+    ///   [gamma](Self::set_gamma) to `1.0`,
+    /// - [rotation](Self::set_rotation) and [mirroring](Self::set_mirror) are turned off,
+    /// - the [origin](Self::set_origin) and [insets](Self::set_insets) go back to zero,
+    /// - the [header offset](Self::with_header_offset) goes back to
+    ///   [`STATUS_BAR_HEIGHT`](Self::STATUS_BAR_HEIGHT), leaving room for VEXos' status bar.
+    ///
+    /// The [storage order](Self::set_storage_byte_order) is kept, since it describes the SDK
+    /// rather than how a program wants things to look. Drawing state like the shadow buffer,
+    /// layers and managed region is kept too, although changing the header offset clears the
+    /// shadow buffer and layers.
+    pub fn reset(&mut self) {
+        self.set_render_mode(RenderMode::Immediate);
+        self.brightness = u8::MAX;
+        self.gamma = 1.0;
+        self.gamma_lut = None;
+        self.rotation = Rotation::Deg0;
+        self.mirror = (false, false);
+        self.origin = Point::zero();
+        self.insets = Insets::ZERO;
+        if self.header_offset != Self::STATUS_BAR_HEIGHT {
+            self.set_header_offset(Self::STATUS_BAR_HEIGHT);
+        }
+        self.clear_black();
+    }
+
+    /// Hands the display back to VEXos, returning the backend.
+    ///
+    /// Without this the last frame stays on screen after a program is done with the display,
+    /// which is easy to mistake for a program that is still running. This is never done
+    /// automatically, so dropping the driver instead leaves the screen as it is. It:
+    ///
+    /// - switches to [`RenderMode::Immediate`], rendering anything still in the back buffer
+    ///   first, so that VEXos' own draws show up right away,
+    /// - clears the whole panel to black, including the header rows in case a
+    ///   [header offset](Self::with_header_offset) below the default let the program draw over
+    ///   them, so VEXos can draw its header onto a clean screen.
+    ///
+    /// The shadow buffer, managed region and other drawing state belong to the driver and go away
+    /// with it.
+    #[must_use]
+    pub fn restore_vexos_display(mut self) -> B {
+        self.set_render_mode(RenderMode::Immediate);
+        self.set_foreground(0);
+        self.display.fill_rect(
+            0,
+            -(HEADER_HEIGHT as i32),
+            WIDTH as i32 - 1,
+            HEIGHT as i32 - 1,
+        );
+        self.display
+    }
+
+    /// Consumes the driver, returning the backend so it can be used directly again.
+    ///
+    /// Unlike [`restore_vexos_display`](Self::restore_vexos_display), this leaves the panel as
+    /// the program last drew it. Anything that hasn't been shown yet is [rendered](Self::render)
+    /// first, so the screen matches everything drawn through the driver, and the render mode is
+    /// left as it was. The shadow buffer, managed region and other drawing state go away with
+    /// the driver.
+    #[must_use]
+    pub fn into_inner(mut self) -> B {
+        self.render();
+        self.display
+    }
+
+    /// Draws everything that has to be on the panel before a frame is shown.
+    fn finish_frame(&mut self) {
+        #[cfg(feature = "stats")]
+        self.draw_debug_overlay();
+        self.flush_managed_region();
+        self.composite_layers();
+        #[cfg(feature = "stats")]
+        self.stats.finish_frame();
+    }
+
+    /// Enables the shadow buffer, an in-memory copy of everything drawn through this driver.
+    ///
+    /// The display can't be read back from, so features that need to know what's already on
+    /// screen (like [blending](Self::blended)) rely on this copy instead. It costs one `u32` per
+    /// pixel (about 460 KB) and starts out black, so it only reflects what was drawn after it was
+    /// enabled. Enabling it again has no effect.
+    pub fn enable_shadow_buffer(&mut self) {
+        if self.shadow.is_none() {
+            let len = WIDTH as usize * self.screen_height() as usize;
+            self.shadow = Some(vec![0; len].into_boxed_slice());
+        }
+    }
+
+    /// Disables the shadow buffer and frees its memory.
+    ///
+    /// The [managed region](Self::set_managed_region) needs the shadow buffer, so it is cleared
+    /// too.
+    pub fn disable_shadow_buffer(&mut self) {
+        self.clear_managed_region();
+        self.shadow = None;
+    }
+
+    /// Returns `true` if the shadow buffer is enabled.
+    #[must_use]
+    pub const fn has_shadow_buffer(&self) -> bool {
+        self.shadow.is_some()
+    }
+
+    /// Copies the whole shadow buffer to the panel, if it is enabled.
+    fn restore_from_shadow(&mut self) {
+        self.copy_shadow_to_panel(&self.screen_bounds());
+    }
+
+    /// Returns the shadow buffer's color at `point`, if it is enabled and `point` is on screen.
+    pub(crate) fn shadow_pixel(&self, point: Point) -> Option<u32> {
+        let shadow = self.shadow.as_ref()?;
+        self.screen_index(point).map(|index| shadow[index])
+    }
+
+    /// Like [`shadow_pixel`](Self::shadow_pixel), but for a point in embedded-graphics
+    /// coordinates.
+    pub(crate) fn stored_pixel(&self, point: Point) -> Option<u32> {
+        self.shadow_pixel(self.to_screen(point)?)
+    }
+
+    fn screen_index(&self, point: Point) -> Option<usize> {
+        self.screen_bounds()
+            .contains(point)
+            .then(|| point.y as usize * WIDTH as usize + point.x as usize)
+    }
+
+    /// Sets the SDK's foreground color to a native color, after the output adjustments, unless
+    /// it is already that color.
+    ///
+    /// Changing the foreground color is an SDK call of its own, so the last color set is
+    /// remembered to skip it for consecutive draws of the same color, like most of the pixels
+    /// of a line of text.
+    ///
+    /// This assumes the driver is the only thing setting the SDK's foreground color. The cache is
+    /// only forgotten by [`backend_mut`](Self::backend_mut), so anything else that changes it,
+    /// like calling the SDK directly, leaves the next draw in the wrong color.
+    pub(crate) fn set_foreground(&mut self, color: u32) {
+        let color = self.panel_color(color);
+        if self.foreground != Some(color) {
+            self.display.set_foreground_color(color);
+            self.foreground = Some(color);
+        }
+    }
+
+    /// Writes a single on-screen pixel.
+    pub(crate) fn write_pixel(&mut self, point: Point, color: u32) {
+        if let (Some(index), Some(shadow)) = (self.screen_index(point), &mut self.shadow) {
+            shadow[index] = color;
+        }
+        let pixel = Rectangle::new(point, Size::new(1, 1));
+        self.dirty.include(&pixel);
+        if self.unmanaged_parts(&pixel).next().is_none() {
+            return;
+        }
+
+        self.set_foreground(color);
+        self.display
+            .set_pixel(point.x as u32, self.sdk_y(point.y) as u32);
+        #[cfg(feature = "stats")]
+        self.stats.record_pixel_set();
+    }
+
+    /// Fills a rectangle with a single color.
+    ///
+    /// Anything outside of the screen is clipped here, so the SDK never sees an off-screen
+    /// rectangle.
+    pub(crate) fn write_rect(&mut self, area: &Rectangle, color: u32) {
+        let visible = area.intersection(&self.screen_bounds());
+        if visible.is_zero_sized() {
+            return;
+        }
+
+        if let Some(shadow) = &mut self.shadow {
+            for y in visible.rows() {
+                let start = y as usize * WIDTH as usize;
+                shadow[start + visible.top_left.x as usize..][..visible.size.width as usize]
+                    .fill(color);
+            }
+        }
+        self.dirty.include(&visible);
+
+        self.set_foreground(color);
+        for part in self.unmanaged_parts(&visible) {
+            let bottom_right = part.top_left + part.size - Point::new(1, 1);
+            self.display.fill_rect(
+                part.top_left.x,
+                self.sdk_y(part.top_left.y),
+                bottom_right.x,
+                self.sdk_y(bottom_right.y),
+            );
+            #[cfg(feature = "stats")]
+            self.stats
+                .record_rect_fill(part.size.width * part.size.height);
+        }
+    }
+
+    /// Fills `area`, in embedded-graphics coordinates, with a color in the display's native format.
+    pub(crate) fn fill_solid_storage(&mut self, area: &Rectangle, color: u32) {
+        let visible = self.clip_to_screen(area);
+        self.write_rect(&visible, color);
+    }
+
+    /// Fills `area`, in embedded-graphics coordinates, with colors in the display's native format.
+    ///
+    /// This is [`DrawTarget::fill_contiguous`] after color conversion, for drawing code that
+    /// already works with native colors. Each visible row is copied to the display on its own,
+    /// which is a column of the screen when it is [rotated](Self::set_rotation) a quarter turn.
+    /// Rows that end up reversed on screen by rotation or [mirroring](Self::set_mirror) are
+    /// reversed in the buffer before they are copied.
+    /// Returns `false` if `colors` runs out before the visible part of `area` is covered, in which
+    /// case only the pixels it had colors for are drawn, and the rest of `area` is left as it
+    /// was.
+    pub(crate) fn fill_contiguous_storage(
+        &mut self,
+        area: &Rectangle,
+        colors: impl IntoIterator<Item = u32>,
+    ) -> bool {
+        self.fill_contiguous_within(area, &self.bounding_box(), colors)
+    }
+
+    /// Like [`fill_contiguous_storage`](Self::fill_contiguous_storage), but only draws the part
+    /// of `area` inside `clip`.
+    pub(crate) fn fill_contiguous_within(
+        &mut self,
+        area: &Rectangle,
+        clip: &Rectangle,
+        colors: impl IntoIterator<Item = u32>,
+    ) -> bool {
+        self.fill_contiguous_keyed(area, clip, colors, None)
+    }
+
+    /// Like [`fill_contiguous_within`](Self::fill_contiguous_within), but leaves the pixels whose
+    /// color is `key`, if there is one, as they were.
+    pub(crate) fn fill_contiguous_keyed(
+        &mut self,
+        area: &Rectangle,
+        clip: &Rectangle,
+        colors: impl IntoIterator<Item = u32>,
+        key: Option<u32>,
+    ) -> bool {
+        let visible = safe_intersection(area, &clip.intersection(&self.bounding_box()));
+        if visible.is_zero_sized() {
+            return true;
+        }
+
+        let width = visible.size.width as usize;
+        let skip_left = visible.top_left.x.abs_diff(area.top_left.x) as usize;
+        let skip_right = area.size.width as usize - skip_left - width;
+        let mut colors = colors.into_iter();
+
+        // Colors for rows above the screen are skipped over. Colors after the last visible row
+        // can't be seen, so stop there rather than consuming the rest of a rectangle that may be
+        // far larger than the screen.
+        let skip_above = (visible.top_left.y.abs_diff(area.top_left.y) as usize)
+            .saturating_mul(area.size.width as usize);
+        if !skip(&mut colors, skip_above) {
+            return false;
+        }
+
+        for y in visible.rows() {
+            if !skip(&mut colors, skip_left) {
+                return false;
+            }
+
+            // Rows are copied in bands that fit the scratch buffer, so this doesn't depend on
+            // the buffer being as wide as the screen.
+            for band_start in (0..width).step_by(self.buffer.len()) {
+                let band = (width - band_start).min(self.buffer.len());
+                let start = Point::new(visible.top_left.x + band_start as i32, y);
+                let mut read = 0;
+                for (pixel, color) in self.buffer[..band].iter_mut().zip(colors.by_ref()) {
+                    *pixel = color;
+                    read += 1;
+                }
+                if read < band {
+                    // Draw what was read of the last band, so that no pixel shows stale buffer
+                    // contents and every color that was given ends up on screen.
+                    if read > 0 {
+                        self.write_row_keyed(start, read as u32, key);
+                    }
+                    return false;
+                }
+
+                self.write_row_keyed(start, band as u32, key);
+            }
+
+            if !skip(&mut colors, skip_right) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Copies the first `width` pixels of the scratch buffer into the row starting at `start`, in
+    /// embedded-graphics coordinates.
+    pub(crate) fn write_row(&mut self, start: Point, width: u32) {
+        // Spans are copied top to bottom and left to right, which is backwards for some
+        // rotations and mirrorings.
+        let span = self.screen_rect(&Rectangle::new(start, Size::new(width, 1)));
+        if self.to_screen(start) != Some(span.top_left) {
+            self.buffer[..width as usize].reverse();
+        }
+
+        self.write_span(&span);
+    }
+
+    /// Like [`write_row`](Self::write_row), but leaves the pixels whose color is `key`, if there
+    /// is one, as they were. Each run of other pixels is copied on its own.
+    fn write_row_keyed(&mut self, start: Point, width: u32, key: Option<u32>) {
+        let Some(key) = key.filter(|key| self.buffer[..width as usize].contains(key)) else {
+            self.write_row(start, width);
+            return;
+        };
+
+        // Writing a run reuses the scratch buffer, so work from a copy of the row.
+        let row = self.buffer;
+        let mut offset = 0;
+        for run in row[..width as usize].split(|&color| color == key) {
+            if !run.is_empty() {
+                self.buffer[..run.len()].copy_from_slice(run);
+                self.write_row(start + Point::new(offset as i32, 0), run.len() as u32);
+            }
+            offset += run.len() + 1;
+        }
+    }
+
+    /// Copies the first `len` pixels of the scratch buffer into the row starting at `start`, in
+    /// screen coordinates.
+    fn write_run(&mut self, start: Point, len: usize) {
+        if len == 1 {
+            self.write_pixel(start, self.buffer[0]);
+        } else {
+            self.write_span(&Rectangle::new(start, Size::new(len as u32, 1)));
+        }
+    }
+
+    /// Copies the start of the scratch buffer into `span`, a rectangle in screen coordinates that
+    /// is a single row or column tall.
+    ///
+    /// Like [`write_rect`](Self::write_rect), only the part of the span on the screen is copied.
+    pub(crate) fn write_span(&mut self, span: &Rectangle) {
+        let visible = span.intersection(&self.screen_bounds());
+        if visible.is_zero_sized() {
+            return;
+        }
+
+        let offset = span_offset(span, visible.top_left);
+        if let Some(shadow) = &mut self.shadow {
+            for (index, point) in visible.points().enumerate() {
+                let pixel = point.y as usize * WIDTH as usize + point.x as usize;
+                shadow[pixel] = self.buffer[offset + index];
+            }
+        }
+        self.dirty.include(&visible);
+
+        for part in self.unmanaged_parts(&visible) {
+            let start = offset + span_offset(&visible, part.top_left);
+            self.copy_span_to_panel(&part, start);
+        }
+    }
+
+    /// Copies a non-empty rectangle of the shadow buffer to the panel, if it is enabled.
+    ///
+    /// This overwrites the scratch buffer if any output color transforms are active.
+    pub(crate) fn copy_shadow_to_panel(&mut self, area: &Rectangle) {
+        let Some(shadow) = &self.shadow else {
+            return;
+        };
+        let width = area.size.width as usize;
+        let start = area.top_left.y as usize * WIDTH as usize + area.top_left.x as usize;
+
+        if !self.has_color_transform() {
+            // The shadow buffer has the same layout as the screen, so the whole area goes out in
+            // one call, with the stride skipping the rest of each row.
+            let y = self.sdk_y(area.top_left.y);
+            self.display.copy_rect(
+                area.top_left.x,
+                y,
+                area.top_left.x + area.size.width as i32 - 1,
+                y + area.size.height as i32 - 1,
+                &shadow[start..],
+                WIDTH as i32,
+            );
+            #[cfg(feature = "stats")]
+            self.stats
+                .record_rect_copy(area.size.width * area.size.height);
+            return;
+        }
+
+        for (row, y) in area.rows().enumerate() {
+            let Some(shadow) = &self.shadow else {
+                return;
+            };
+            let start = start + row * WIDTH as usize;
+            self.buffer[..width].copy_from_slice(&shadow[start..][..width]);
+
+            let row = Rectangle::new(
+                Point::new(area.top_left.x, y),
+                Size::new(area.size.width, 1),
+            );
+            self.copy_span_to_panel(&row, 0);
+        }
+    }
+
+    /// Copies a non-empty span, a single row or column rectangle, from the scratch buffer to the
+    /// panel.
+    ///
+    /// The span's first pixel is at `start` in the buffer. Output color transforms like the
+    /// [storage order](Self::set_storage_byte_order) are applied to the buffer in place on the way.
+    fn copy_span_to_panel(&mut self, span: &Rectangle, start: usize) {
+        let len = (span.size.width * span.size.height) as usize;
+        if self.has_color_transform() {
+            for index in start..start + len {
+                self.buffer[index] = self.panel_color(self.buffer[index]);
+            }
+        }
+
+        let y = self.sdk_y(span.top_left.y);
+        self.display.copy_rect(
+            span.top_left.x,
+            y,
+            span.top_left.x + span.size.width as i32 - 1,
+            y + span.size.height as i32 - 1,
+            &self.buffer[start..],
+            span.size.width as i32,
+        );
+        #[cfg(feature = "stats")]
+        self.stats.record_rect_copy(len as u32);
+    }
+}
+
+/// Returns how far `point` is along `span`, a single row or column rectangle.
+fn span_offset(span: &Rectangle, point: Point) -> usize {
+    let offset = point - span.top_left;
+    (offset.x + offset.y) as usize
+}
+
+/// Skips the next `count` colors, returning `false` if there weren't that many.
+fn skip<T>(colors: &mut impl Iterator<Item = T>, count: usize) -> bool {
+    count == 0 || colors.nth(count - 1).is_some()
 }
 
-impl OriginDimensions for DisplayDriver {
+impl<B: PanelBackend> From<B> for DisplayDriver<Rgb888, B> {
+    /// Creates a driver with [`DisplayDriver::new`].
+    fn from(display: B) -> Self {
+        Self::new(display)
+    }
+}
+
+impl<C, B> OriginDimensions for DisplayDriver<C, B> {
     fn size(&self) -> Size {
-        Size {
-            width: Display::HORIZONTAL_RESOLUTION as _,
-            height: Display::VERTICAL_RESOLUTION as _,
-        }
+        self.logical_size()
     }
 }
 
-impl DrawTarget for DisplayDriver {
-    type Color = Rgb888;
+impl<C: ToDisplayStorage, B: PanelBackend> DrawTarget for DisplayDriver<C, B> {
+    type Color = C;
 
-    type Error = Infallible;
+    type Error = DisplayError;
 
     fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        pixels.into_iter().for_each(|Pixel(pos, color)| {
-            if pos.x >= 0
-                && pos.x < Display::HORIZONTAL_RESOLUTION as i32
-                && pos.y >= 0
-                && pos.y < Display::VERTICAL_RESOLUTION as i32
-            {
-                unsafe {
-                    vex_sdk::vexDisplayForegroundColor(color.into_storage());
-                    vex_sdk::vexDisplayPixelSet(pos.x as u32, pos.y as u32);
+        // Drawables like text and anti-aliased shapes tend to draw pixels left to right, so runs
+        // of pixels that are next to each other on screen are gathered in the scratch buffer and
+        // copied to the panel with one SDK call. Scattered pixels are still drawn one at a time.
+        let mut run: Option<(Point, usize)> = None;
+        for Pixel(pos, color) in pixels {
+            let Some(pos) = self.to_screen(pos) else {
+                continue;
+            };
+
+            match &mut run {
+                Some((start, len)) if pos == *start + Point::new(*len as i32, 0) => {
+                    self.buffer[*len] = color.to_display_storage();
+                    *len += 1;
+                }
+                _ => {
+                    if let Some((start, len)) = run {
+                        self.write_run(start, len);
+                    }
+                    self.buffer[0] = color.to_display_storage();
+                    run = Some((pos, 1));
                 }
             }
-        });
+        }
+        if let Some((start, len)) = run {
+            self.write_run(start, len);
+        }
 
         Ok(())
     }
 
-    // Note: clear is not implemented because vexDisplayErase does not allow
-    // changing the background color.
-
-    fn fill_contiguous<I>(
-        &mut self,
-        area: &embedded_graphics_core::primitives::Rectangle,
-        colors: I,
-    ) -> Result<(), Self::Error>
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
     where
         I: IntoIterator<Item = Self::Color>,
     {
-        if let Some(bottom_right) = area.bottom_right() {
-            // Copy the colors into the buffer
-            colors.into_iter().enumerate().for_each(|(i, color)| {
-                self.buffer[i] = color.into_storage();
-            });
-            // Copy the buffer to the display
-            unsafe {
-                vexDisplayCopyRect(
-                    area.top_left.x,
-                    area.top_left.y,
-                    bottom_right.x,
-                    bottom_right.y,
-                    self.buffer.as_mut_ptr(),
-                    area.size.width as i32,
-                );
-            }
+        if self.fill_contiguous_storage(area, colors.into_iter().map(C::to_display_storage)) {
+            Ok(())
+        } else {
+            Err(DisplayError::ColorBufferTooSmall)
         }
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.fill_solid_storage(area, color.to_display_storage());
 
         Ok(())
     }
 
-    fn fill_solid(
-        &mut self,
-        area: &embedded_graphics_core::primitives::Rectangle,
-        color: Self::Color,
-    ) -> Result<(), Self::Error> {
-        if let Some(bottom_right) = area.bottom_right() {
-            unsafe {
-                vexDisplayForegroundColor(color.into_storage());
-                vexDisplayRectFill(
-                    area.top_left.x,
-                    area.top_left.y,
-                    bottom_right.x,
-                    bottom_right.y,
-                );
-            }
-        }
+    // vexDisplayErase always erases to the SDK's background color, so clearing is a single solid
+    // fill of the drawable area instead.
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.fill_solid_storage(&self.bounding_box(), color.to_display_storage());
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use super::*;
+
+    /// Runs a [`DisplayDriver::flush_and_wait`] on a mock panel, which never has to wait.
+    fn flush_and_wait(display: &mut DisplayDriver<Rgb888, MockPanel>) {
+        let poll = pin!(display.flush_and_wait()).poll(&mut Context::from_waker(Waker::noop()));
+        assert_eq!(poll, Poll::Ready(()));
+    }
+
+    #[test]
+    fn flush_and_wait_returns_once_the_frame_is_complete() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.set_render_mode(RenderMode::DoubleBuffered);
+        display
+            .fill_solid(&Rectangle::new(Point::zero(), Size::new(4, 4)), Rgb888::RED)
+            .unwrap();
+
+        flush_and_wait(&mut display);
+
+        assert_eq!(display.backend().renders(), 1);
+        assert_eq!(display.backend().completed_frames(), 1);
+        assert_eq!(display.dirty_area(), None);
+    }
+
+    #[test]
+    fn flush_and_wait_waits_in_immediate_mode_too() {
+        let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+
+        flush_and_wait(&mut display);
+        assert_eq!(display.backend().renders(), 0);
+        assert_eq!(display.backend().completed_frames(), 1);
+    }
+
+    #[test]
+    fn render_does_not_wait_for_the_frame() {
+        let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+        display.set_render_mode(RenderMode::DoubleBuffered);
+
+        display.render();
+        assert_eq!(display.backend().renders(), 1);
+        assert_eq!(display.backend().completed_frames(), 0);
+    }
+
+    #[test]
+    fn flush_and_wait_composites_layers_before_waiting() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let id = display.add_layer();
+        display
+            .layer(id)
+            .draw_iter([Pixel(Point::new(2, 3), Rgb888::GREEN)])
+            .unwrap();
+
+        flush_and_wait(&mut display);
+
+        assert_eq!(display.backend().pixel(2, 3), Some(0x00FF00));
+        assert_eq!(display.backend().completed_frames(), 1);
+    }
+
+    #[test]
+    fn with_clear_fills_the_whole_screen_on_construction() {
+        let display = DisplayDriver::with_clear(MockPanel::new(), Rgb888::BLUE);
+
+        let panel = display.backend();
+        assert_eq!(panel.fill_rects().len(), 1);
+        assert!(panel.framebuffer().iter().all(|&color| color == 0x0000FF));
+    }
+
+    #[test]
+    fn with_clear_sets_the_background_color() {
+        let mut display = DisplayDriver::with_clear(MockPanel::new(), Rgb888::BLUE);
+        display
+            .fill_solid(&Rectangle::new(Point::zero(), Size::new(4, 4)), Rgb888::RED)
+            .unwrap();
+
+        display.clear_widget_area(&Rectangle::new(Point::zero(), Size::new(2, 2)));
+
+        assert_eq!(display.backend().pixel(1, 1), Some(0x0000FF));
+        assert_eq!(display.backend().pixel(2, 2), Some(0xFF0000));
+    }
+
+    #[test]
+    fn switching_to_immediate_renders_the_back_buffer_first() {
+        let mut display =
+            DisplayDriver::with_render_mode(MockPanel::new(), RenderMode::DoubleBuffered);
+        display.set_managed_region(Rectangle::new(Point::zero(), Size::new(10, 10)));
+        display.set_pixel(Point::new(1, 1), Rgb888::RED);
+
+        display.set_render_mode(RenderMode::Immediate);
+
+        assert_eq!(display.backend().renders(), 1);
+        assert_eq!(display.backend().render_mode(), RenderMode::Immediate);
+        assert_eq!(display.backend().pixel(1, 1), Some(0xFF0000));
+    }
+
+    #[test]
+    fn switching_to_double_buffered_keeps_the_shadowed_content() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.enable_shadow_buffer();
+        display.set_pixel(Point::new(1, 1), Rgb888::RED);
+        // The SDK's back buffer starts out without what was drawn straight to the panel.
+        display.backend_mut().set_foreground_color(0);
+        display.backend_mut().fill_rect(0, 0, 479, 239);
+
+        display.set_render_mode(RenderMode::DoubleBuffered);
+
+        assert_eq!(display.backend().pixel(1, 1), Some(0xFF0000));
+        assert_eq!(display.backend().renders(), 0);
+    }
+
+    #[test]
+    fn setting_the_same_render_mode_does_nothing() {
+        let mut display = DisplayDriver::<Rgb888, _>::with_render_mode(
+            MockPanel::new(),
+            RenderMode::DoubleBuffered,
+        );
+
+        display.set_render_mode(RenderMode::DoubleBuffered);
+
+        assert_eq!(display.backend().renders(), 0);
+    }
+
+    #[test]
+    fn restore_vexos_display_resets_and_clears_the_panel() {
+        let mut display =
+            DisplayDriver::with_render_mode(MockPanel::new(), RenderMode::DoubleBuffered);
+        display.clear(Rgb888::RED).unwrap();
+
+        let panel = display.restore_vexos_display();
+
+        assert_eq!(panel.render_mode(), RenderMode::Immediate);
+        assert_eq!(panel.renders(), 1);
+        assert_eq!(panel.fill_rects().len(), 2);
+        assert!(panel.framebuffer().iter().all(|&color| color == 0));
+    }
+
+    #[test]
+    fn into_inner_leaves_the_last_frame_on_screen() {
+        let mut display =
+            DisplayDriver::with_render_mode(MockPanel::new(), RenderMode::DoubleBuffered);
+        display.clear(Rgb888::RED).unwrap();
+
+        let panel = display.into_inner();
+
+        assert_eq!(panel.render_mode(), RenderMode::DoubleBuffered);
+        assert_eq!(panel.renders(), 1);
+        assert!(panel.framebuffer().iter().all(|&color| color == 0xFF0000));
+    }
+
+    #[test]
+    fn oversized_fill_contiguous_draws_only_the_visible_part() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let area = Rectangle::new(Point::new(-50, -50), Size::new(300, 300));
+        // Each color encodes where in the area it is drawn, modulo 256.
+        let colors = area
+            .points()
+            .map(|point| Rgb888::new((point.x + 50) as u8, (point.y + 50) as u8, 1));
+
+        display.fill_contiguous(&area, colors).unwrap();
+
+        let panel = display.backend();
+        assert_eq!(panel.pixel(0, 0), Some(0x323201));
+        assert_eq!(panel.pixel(200, 190), Some(0xFAF001));
+        assert_eq!(panel.pixel(249, 239), Some(0x2B2101));
+        assert_eq!(panel.pixel(250, 239), Some(0));
+    }
+
+    #[test]
+    fn fill_contiguous_stops_reading_colors_after_the_last_visible_row() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let area = Rectangle::new(Point::new(0, 200), Size::new(10, 100));
+        let mut read = 0;
+
+        display
+            .fill_contiguous(
+                &area,
+                core::iter::repeat(Rgb888::RED).inspect(|_| read += 1),
+            )
+            .unwrap();
+
+        assert_eq!(read, 10 * 40);
+        assert_eq!(display.backend().pixel(9, 239), Some(0xFF0000));
+    }
+
+    #[test]
+    fn fill_solid_only_requests_the_on_screen_part() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(470, 10), Size::new(20, 5)),
+                Rgb888::RED,
+            )
+            .unwrap();
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(-5, -5), Size::new(10, 10)),
+                Rgb888::RED,
+            )
+            .unwrap();
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(480, 0), Size::new(10, 10)),
+                Rgb888::RED,
+            )
+            .unwrap();
+
+        assert_eq!(
+            display.backend().fill_rects(),
+            [
+                Rectangle::new(Point::new(470, 10), Size::new(10, 5)),
+                Rectangle::new(Point::zero(), Size::new(5, 5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn fill_solid_is_clipped_to_the_drawable_area() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.set_insets(Insets::new(0, 20, 0, 0));
+        display.set_origin(Point::new(0, 100));
+
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(450, -10), Size::new(20, 20)),
+                Rgb888::RED,
+            )
+            .unwrap();
+
+        assert_eq!(
+            display.backend().fill_rects(),
+            [Rectangle::new(Point::new(450, 100), Size::new(10, 10))]
+        );
+    }
+
+    #[test]
+    fn fill_solid_and_fill_contiguous_cover_the_same_pixels() {
+        for size in [Size::new(1, 1), Size::new(2, 2)] {
+            let area = Rectangle::new(Point::new(10, 20), size);
+            let mut solid = DisplayDriver::new(MockPanel::new());
+            solid.fill_solid(&area, Rgb888::RED).unwrap();
+            let mut contiguous = DisplayDriver::new(MockPanel::new());
+            let colors = core::iter::repeat_n(Rgb888::RED, 4);
+            contiguous.fill_contiguous(&area, colors).unwrap();
+
+            // The SDK's corners are inclusive, so a 1x1 fill passes the same point twice.
+            assert_eq!(solid.backend().fill_rects(), [area]);
+            let drawn = solid
+                .backend()
+                .framebuffer()
+                .iter()
+                .filter(|&&c| c != 0)
+                .count();
+            assert_eq!(drawn, (size.width * size.height) as usize);
+            assert!(
+                solid.backend().framebuffer() == contiguous.backend().framebuffer(),
+                "{size:?} fills differ"
+            );
+        }
+    }
+
+    #[test]
+    fn consecutive_fills_of_one_color_set_the_foreground_once() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let before = display.backend().foreground_changes();
+
+        for x in 0..3 {
+            display
+                .fill_solid(
+                    &Rectangle::new(Point::new(x * 10, 0), Size::new(5, 5)),
+                    Rgb888::RED,
+                )
+                .unwrap();
+        }
+        assert_eq!(display.backend().foreground_changes(), before + 1);
+
+        display
+            .fill_solid(
+                &Rectangle::new(Point::zero(), Size::new(5, 5)),
+                Rgb888::BLUE,
+            )
+            .unwrap();
+        assert_eq!(display.backend().foreground_changes(), before + 2);
+    }
+
+    #[test]
+    fn backend_mut_forgets_the_foreground_color() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let area = Rectangle::new(Point::zero(), Size::new(5, 5));
+        display.fill_solid(&area, Rgb888::RED).unwrap();
+        display.backend_mut().set_foreground_color(0x0000FF);
+        let before = display.backend().foreground_changes();
+
+        display.fill_solid(&area, Rgb888::RED).unwrap();
+
+        assert_eq!(display.backend().foreground_changes(), before + 1);
+        assert_eq!(display.backend().pixel(0, 0), Some(0xFF0000));
+    }
+}
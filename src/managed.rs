@@ -0,0 +1,304 @@
+//! Buffering draws to part of the screen so they show up all at once.
+
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use crate::{DisplayDriver, DisplayError, PanelBackend, dirty::DirtyRegion};
+
+/// A part of the screen whose draws are held in the shadow buffer until the next render.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ManagedRegion {
+    area: Rectangle,
+    pending: DirtyRegion,
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Confines software double buffering to `area`.
+    ///
+    /// Draws inside the managed region only update the [shadow buffer](Self::enable_shadow_buffer),
+    /// which is enabled by this call. The part of it that changed is then copied to the panel in
+    /// a single transfer on the next [`render`](Self::render), so something like a small animated
+    /// widget never shows up half drawn. Draws outside the region still go straight to the panel,
+    /// which keeps static parts of the UI cheap. A draw that crosses the boundary is split: the
+    /// part inside waits for the render and the rest shows up right away.
+    ///
+    /// `area` is in embedded-graphics coordinates, clipped to the drawable area when it is set. Any
+    /// draws still waiting in a previous managed region are flushed first.
+    pub fn set_managed_region(&mut self, area: Rectangle) {
+        self.flush_managed_region();
+        self.enable_shadow_buffer();
+        self.managed = Some(ManagedRegion {
+            area: self.clip_to_screen(&area),
+            pending: DirtyRegion::default(),
+        });
+    }
+
+    /// Flushes any pending draws in the managed region and goes back to drawing everything
+    /// straight to the panel.
+    pub fn clear_managed_region(&mut self) {
+        self.flush_managed_region();
+        self.managed = None;
+    }
+
+    /// Returns the managed region in screen coordinates, if one is set.
+    ///
+    /// See [`dirty_area`](Self::dirty_area) for how screen coordinates differ from the driver's.
+    #[must_use]
+    pub fn managed_region(&self) -> Option<Rectangle> {
+        self.managed.map(|managed| managed.area)
+    }
+
+    /// Marks the part of `area` inside the managed region as pending, and returns the parts
+    /// outside of it that should be drawn to the panel right away.
+    pub(crate) fn unmanaged_parts(
+        &mut self,
+        area: &Rectangle,
+    ) -> impl Iterator<Item = Rectangle> + use<C, B> {
+        let parts = match &mut self.managed {
+            Some(managed) => {
+                let inside = area.intersection(&managed.area);
+                managed.pending.include(&inside);
+                subtract(area, &inside)
+            }
+            None => subtract(area, &Rectangle::zero()),
+        };
+
+        parts.into_iter().filter(|part| !part.is_zero_sized())
+    }
+
+    /// Shows the draws waiting in the part of the managed region covered by `area`, which is in
+    /// embedded-graphics coordinates, and leaves the rest of the frame alone.
+    ///
+    /// This is for callers that track what changed themselves. The pending draws inside both
+    /// `area` and the [managed region](Self::set_managed_region) are copied from the shadow
+    /// buffer to the panel, with a single SDK call unless output adjustments like the
+    /// [brightness](Self::set_brightness) are active, and nothing else is drawn. Without a
+    /// managed region nothing is being held back, so there's nothing to do.
+    ///
+    /// This only works in [`RenderMode::Immediate`](crate::RenderMode::Immediate). The SDK can
+    /// only present its double buffer as a whole, and turning double buffering back on after
+    /// drawing straight to the panel presents it too, so there's no way to show just part of a
+    /// double buffered frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DisplayError::UnsupportedRenderMode`] without drawing anything if the display is
+    /// in [`RenderMode::DoubleBuffered`](crate::RenderMode::DoubleBuffered).
+    pub fn render_region(&mut self, area: Rectangle) -> Result<(), DisplayError> {
+        if self.is_double_buffered() {
+            return Err(DisplayError::UnsupportedRenderMode);
+        }
+
+        let area = self.clip_to_screen(&area);
+        let Some(managed) = &mut self.managed else {
+            return Ok(());
+        };
+        let Some(pending) = managed.pending.area() else {
+            return Ok(());
+        };
+
+        let shown = pending.intersection(&area);
+        if shown.is_zero_sized() {
+            return Ok(());
+        }
+
+        // The pending area is a single bounding box, so it can only shrink when all of it was
+        // shown. Otherwise the shown part is copied again by the next full flush.
+        if shown == pending {
+            managed.pending.clear();
+        }
+        self.copy_shadow_to_panel(&shown);
+
+        Ok(())
+    }
+
+    /// Copies the part of the managed region that changed since the last flush to the panel.
+    pub(crate) fn flush_managed_region(&mut self) {
+        if let Some(pending) = self.take_managed_pending() {
+            self.copy_shadow_to_panel(&pending);
+        }
+    }
+
+    /// Returns the part of the managed region that changed since the last flush, and marks it
+    /// as flushed.
+    pub(crate) fn take_managed_pending(&mut self) -> Option<Rectangle> {
+        let managed = self.managed.as_mut()?;
+        let pending = managed.pending.area();
+        managed.pending.clear();
+        pending
+    }
+}
+
+/// Returns up to four rectangles that cover the parts of `area` outside of `hole`, which must be
+/// inside `area` or zero sized.
+fn subtract(area: &Rectangle, hole: &Rectangle) -> [Rectangle; 4] {
+    if hole.is_zero_sized() {
+        return [
+            *area,
+            Rectangle::zero(),
+            Rectangle::zero(),
+            Rectangle::zero(),
+        ];
+    }
+
+    let area_end = area.top_left + area.size;
+    let hole_end = hole.top_left + hole.size;
+
+    [
+        // Above the hole
+        Rectangle::new(
+            area.top_left,
+            Size::new(area.size.width, (hole.top_left.y - area.top_left.y) as u32),
+        ),
+        // Below the hole
+        Rectangle::new(
+            Point::new(area.top_left.x, hole_end.y),
+            Size::new(area.size.width, (area_end.y - hole_end.y) as u32),
+        ),
+        // Left of the hole
+        Rectangle::new(
+            Point::new(area.top_left.x, hole.top_left.y),
+            Size::new((hole.top_left.x - area.top_left.x) as u32, hole.size.height),
+        ),
+        // Right of the hole
+        Rectangle::new(
+            Point::new(hole_end.x, hole.top_left.y),
+            Size::new((area_end.x - hole_end.x) as u32, hole.size.height),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::pixelcolor::Rgb888;
+
+    use super::*;
+    use crate::{MockPanel, RenderMode};
+
+    fn managed_display(mode: RenderMode) -> DisplayDriver<Rgb888, MockPanel> {
+        let mut display = DisplayDriver::with_render_mode(MockPanel::new(), mode);
+        display.set_managed_region(Rectangle::new(Point::zero(), Size::new(100, 100)));
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(10, 10), Size::new(20, 20)),
+                Rgb888::RED,
+            )
+            .unwrap();
+        display
+    }
+
+    #[test]
+    fn render_region_shows_only_the_requested_part() {
+        let mut display = managed_display(RenderMode::Immediate);
+        assert_eq!(display.backend().pixel(15, 15), Some(0));
+
+        display
+            .render_region(Rectangle::new(Point::new(10, 10), Size::new(10, 20)))
+            .unwrap();
+
+        let panel = display.backend();
+        assert_eq!(panel.pixel(15, 15), Some(0xFF0000));
+        assert_eq!(panel.pixel(25, 15), Some(0));
+        assert_eq!(panel.renders(), 0);
+    }
+
+    #[test]
+    fn render_region_applies_output_adjustments() {
+        let mut display = managed_display(RenderMode::Immediate);
+        display.set_brightness(128);
+
+        display.render_region(display.bounding_box()).unwrap();
+
+        assert_eq!(display.backend().pixel(29, 29), Some(0x800000));
+        assert_eq!(display.backend().pixel(30, 30), Some(0));
+    }
+
+    #[test]
+    fn render_region_fails_when_double_buffered() {
+        let mut display = managed_display(RenderMode::DoubleBuffered);
+
+        assert_eq!(
+            display.render_region(display.bounding_box()),
+            Err(DisplayError::UnsupportedRenderMode)
+        );
+
+        let panel = display.backend();
+        assert_eq!(panel.pixel(15, 15), Some(0));
+        assert_eq!(panel.renders(), 0);
+    }
+
+    #[test]
+    fn render_region_without_managed_region_does_nothing() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(10, 10), Size::new(20, 20)),
+                Rgb888::RED,
+            )
+            .unwrap();
+
+        display.render_region(display.bounding_box()).unwrap();
+
+        assert_eq!(display.backend().pixel(15, 15), Some(0xFF0000));
+        assert_eq!(display.backend().renders(), 0);
+    }
+
+    #[test]
+    fn draws_inside_the_managed_region_wait_for_the_render() {
+        let mut display = managed_display(RenderMode::Immediate);
+        assert_eq!(display.backend().pixel(15, 15), Some(0));
+
+        display.render();
+
+        assert_eq!(display.backend().pixel(15, 15), Some(0xFF0000));
+        assert_eq!(
+            display.managed_region(),
+            Some(Rectangle::new(Point::zero(), Size::new(100, 100)))
+        );
+    }
+
+    #[test]
+    fn draws_crossing_the_boundary_are_split() {
+        let mut display = managed_display(RenderMode::Immediate);
+        let crossing = Rectangle::new(Point::new(90, 50), Size::new(20, 1));
+
+        display.fill_solid(&crossing, Rgb888::BLUE).unwrap();
+
+        let panel = display.backend();
+        assert_eq!(panel.pixel(99, 50), Some(0));
+        assert_eq!(panel.pixel(100, 50), Some(0x0000FF));
+        assert_eq!(panel.pixel(109, 50), Some(0x0000FF));
+
+        display.render();
+        assert_eq!(display.backend().pixel(90, 50), Some(0x0000FF));
+    }
+
+    #[test]
+    fn render_only_flushes_what_changed_in_the_managed_region() {
+        let mut display = managed_display(RenderMode::Immediate);
+        // Paint over the panel behind the driver's back, inside and outside of the region.
+        let panel = display.backend_mut();
+        panel.set_foreground_color(0x00FF00);
+        panel.fill_rect(0, 0, 479, 239);
+
+        display.render();
+
+        let panel = display.backend();
+        assert_eq!(panel.pixel(10, 10), Some(0xFF0000));
+        assert_eq!(panel.pixel(29, 29), Some(0xFF0000));
+        assert_eq!(panel.pixel(30, 30), Some(0x00FF00));
+        assert_eq!(panel.pixel(5, 5), Some(0x00FF00));
+        assert_eq!(panel.pixel(200, 200), Some(0x00FF00));
+    }
+
+    #[test]
+    fn clearing_the_managed_region_flushes_it() {
+        let mut display = managed_display(RenderMode::Immediate);
+
+        display.clear_managed_region();
+
+        assert_eq!(display.managed_region(), None);
+        assert_eq!(display.backend().pixel(15, 15), Some(0xFF0000));
+        display.set_pixel(Point::new(50, 50), Rgb888::BLUE);
+        assert_eq!(display.backend().pixel(50, 50), Some(0x0000FF));
+    }
+}
@@ -0,0 +1,284 @@
+//! An in-memory panel for testing drawing code on the host.
+
+use std::ffi::{CStr, CString};
+
+use embedded_graphics_core::{geometry::Point, primitives::Rectangle};
+use vexide::display::Display;
+
+use crate::{
+    DisplayDriver, PanelBackend, RenderMode, TouchEvent, TouchState, backend::sealed::Sealed,
+};
+
+/// An in-memory [`PanelBackend`] for exercising drawing code without a brain.
+///
+/// Draws are applied to a framebuffer that can be inspected with [`pixel`](Self::pixel) and
+/// [`framebuffer`](Self::framebuffer), and touch input can be simulated with
+/// [`set_touch_status`](Self::set_touch_status). Out-of-bounds draws are clipped, so the mock is
+/// a faithful check of what would have shown up on the real panel.
+///
+/// The mock is only compiled with the `mock` feature, so it isn't part of the API of programs
+/// that don't test against it. It doesn't need a brain or an SDK: it receives the same calls that
+/// would have gone to `vexDisplayPixelSet`, `vexDisplayRectFill` and `vexDisplayCopyRect`, in the
+/// same coordinates, so coordinate handling like the [origin](crate::DisplayDriver::set_origin)
+/// and [rotation](crate::DisplayDriver::set_rotation) can be tested on the host.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics_core::{pixelcolor::Rgb888, prelude::*};
+/// use vexide_embedded_graphics::{DisplayDriver, MockPanel};
+///
+/// let mut display = DisplayDriver::new(MockPanel::new());
+/// display.set_origin(Point::new(100, 50));
+/// display.set_pixel(Point::new(1, 2), Rgb888::RED);
+///
+/// assert_eq!(display.backend().pixel(101, 52), Some(0xFF0000));
+/// assert_eq!(display.backend().framebuffer().iter().filter(|&&c| c != 0).count(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockPanel {
+    framebuffer: Vec<u32>,
+    foreground: u32,
+    render_mode: RenderMode,
+    touch: TouchEvent,
+    renders: usize,
+    completed_frames: usize,
+    foreground_changes: usize,
+    fill_rects: Vec<Rectangle>,
+    text: Vec<(Point, u32, CString)>,
+}
+
+impl MockPanel {
+    /// Width of the mock framebuffer.
+    pub const WIDTH: u32 = Display::HORIZONTAL_RESOLUTION as _;
+
+    /// Height of the mock framebuffer.
+    pub const HEIGHT: u32 = Display::VERTICAL_RESOLUTION as _;
+
+    /// Width of each character drawn with [`draw_text`](PanelBackend::draw_text).
+    pub const CHAR_WIDTH: u32 = 10;
+
+    /// Creates a new mock panel with a black framebuffer.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            framebuffer: vec![0; (Self::WIDTH * Self::HEIGHT) as usize],
+            foreground: 0,
+            render_mode: RenderMode::Immediate,
+            touch: TouchEvent {
+                state: TouchState::Released,
+                point: Point::zero(),
+                press_count: 0,
+                release_count: 0,
+            },
+            renders: 0,
+            completed_frames: 0,
+            foreground_changes: 0,
+            fill_rects: Vec::new(),
+            text: Vec::new(),
+        }
+    }
+
+    /// Returns the framebuffer in row-major order, [`WIDTH`](Self::WIDTH) pixels per row.
+    #[must_use]
+    pub fn framebuffer(&self) -> &[u32] {
+        &self.framebuffer
+    }
+
+    /// Returns the color at the given pixel, or `None` if it is outside the framebuffer.
+    #[must_use]
+    pub fn pixel(&self, x: i32, y: i32) -> Option<u32> {
+        Self::index(x, y).map(|index| self.framebuffer[index])
+    }
+
+    /// Returns the number of times [`render`](PanelBackend::render) has flushed the double buffer.
+    #[must_use]
+    pub const fn renders(&self) -> usize {
+        self.renders
+    }
+
+    /// Returns every piece of text drawn with [`draw_text`](PanelBackend::draw_text), in the order
+    /// it was drawn, as its position, color and contents.
+    ///
+    /// The mock has no fonts, so text never shows up in the framebuffer. For measuring, every
+    /// character is [`CHAR_WIDTH`](Self::CHAR_WIDTH) pixels wide.
+    #[must_use]
+    pub fn text(&self) -> &[(Point, u32, CString)] {
+        &self.text
+    }
+
+    /// Returns the number of frames [`render_and_wait`](PanelBackend::render_and_wait) has waited
+    /// for.
+    #[must_use]
+    pub const fn completed_frames(&self) -> usize {
+        self.completed_frames
+    }
+
+    /// Returns the number of times [`set_foreground_color`](PanelBackend::set_foreground_color)
+    /// has been called, whether or not it changed the color.
+    #[must_use]
+    pub const fn foreground_changes(&self) -> usize {
+        self.foreground_changes
+    }
+
+    /// Returns every rectangle passed to [`fill_rect`](PanelBackend::fill_rect), in the order
+    /// they were filled, to check which SDK calls a draw makes.
+    ///
+    /// Rectangles are in the mock's coordinates, as requested, before they are clipped to the
+    /// framebuffer.
+    #[must_use]
+    pub fn fill_rects(&self) -> &[Rectangle] {
+        &self.fill_rects
+    }
+
+    /// Sets the touch event returned by [`touch_status`](PanelBackend::touch_status).
+    pub fn set_touch_status(&mut self, touch: TouchEvent) {
+        self.touch = touch;
+    }
+
+    fn index(x: i32, y: i32) -> Option<usize> {
+        if (0..Self::WIDTH as i32).contains(&x) && (0..Self::HEIGHT as i32).contains(&y) {
+            Some(y as usize * Self::WIDTH as usize + x as usize)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for MockPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sealed for MockPanel {}
+
+impl PanelBackend for MockPanel {
+    fn set_render_mode(&mut self, mode: RenderMode) {
+        self.render_mode = mode;
+    }
+
+    fn render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
+
+    fn render(&mut self) {
+        if let RenderMode::DoubleBuffered = self.render_mode {
+            self.renders += 1;
+        }
+    }
+
+    async fn render_and_wait(&mut self) {
+        self.render();
+        self.completed_frames += 1;
+    }
+
+    fn touch_status(&self) -> TouchEvent {
+        self.touch
+    }
+
+    fn set_foreground_color(&mut self, color: u32) {
+        self.foreground = color;
+        self.foreground_changes += 1;
+    }
+
+    fn set_pixel(&mut self, x: u32, y: u32) {
+        if let Some(index) = Self::index(x as i32, y as i32) {
+            self.framebuffer[index] = self.foreground;
+        }
+    }
+
+    fn fill_rect(&mut self, x1: i32, y1: i32, x2: i32, y2: i32) {
+        self.fill_rects.push(Rectangle::with_corners(
+            Point::new(x1, y1),
+            Point::new(x2, y2),
+        ));
+        for y in y1..=y2 {
+            for x in x1..=x2 {
+                if let Some(index) = Self::index(x, y) {
+                    self.framebuffer[index] = self.foreground;
+                }
+            }
+        }
+    }
+
+    fn copy_rect(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, buffer: &[u32], stride: i32) {
+        for (row, y) in (y1..=y2).enumerate() {
+            for (column, x) in (x1..=x2).enumerate() {
+                let source = row * stride as usize + column;
+                if let (Some(index), Some(&color)) = (Self::index(x, y), buffer.get(source)) {
+                    self.framebuffer[index] = color;
+                }
+            }
+        }
+    }
+
+    fn draw_text(&mut self, x: i32, y: i32, text: &CStr) {
+        self.text
+            .push((Point::new(x, y), self.foreground, text.to_owned()));
+    }
+
+    fn text_width(&self, text: &CStr) -> i32 {
+        text.to_string_lossy().chars().count() as i32 * Self::CHAR_WIDTH as i32
+    }
+}
+
+impl<C> DisplayDriver<C, MockPanel> {
+    /// Returns the mock panel's framebuffer, like [`MockPanel::framebuffer`].
+    #[must_use]
+    pub fn mock_framebuffer(&self) -> &[u32] {
+        self.backend().framebuffer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::pixelcolor::{Rgb888, RgbColor};
+
+    use super::*;
+    use crate::{DisplayDriver, TouchState};
+
+    fn touch(state: TouchState, point: Point) -> TouchEvent {
+        TouchEvent {
+            state,
+            point,
+            press_count: 1,
+            release_count: 0,
+        }
+    }
+
+    #[test]
+    fn mock_touches_reach_the_driver_in_its_coordinates() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display
+            .backend_mut()
+            .set_touch_status(touch(TouchState::Pressed, Point::new(40, 50)));
+
+        assert_eq!(display.touch_status().state, TouchState::Pressed);
+        assert_eq!(display.take_touch(), Some(Point::new(40, 50)));
+        assert_eq!(display.take_touch(), None);
+    }
+
+    #[test]
+    fn mock_render_mode_round_trips() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        assert_eq!(display.render_mode(), RenderMode::Immediate);
+
+        display.set_render_mode(RenderMode::DoubleBuffered);
+        display.render();
+
+        assert_eq!(display.render_mode(), RenderMode::DoubleBuffered);
+        assert_eq!(display.backend().renders(), 1);
+    }
+
+    #[test]
+    fn mock_framebuffer_is_the_panel_framebuffer() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.set_origin(Point::new(100, 50));
+        display.set_pixel(Point::new(1, 2), Rgb888::RED);
+
+        let framebuffer = display.mock_framebuffer();
+        assert_eq!(framebuffer[52 * MockPanel::WIDTH as usize + 101], 0xFF0000);
+        assert_eq!(framebuffer.iter().filter(|&&color| color != 0).count(), 1);
+    }
+}
@@ -0,0 +1,192 @@
+//! Rotating and mirroring what's drawn to match how the brain is mounted.
+
+use embedded_graphics_core::prelude::*;
+
+use crate::DisplayDriver;
+
+/// How far everything drawn is rotated clockwise on the panel.
+///
+/// See [`DisplayDriver::set_rotation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Rotation {
+    /// No rotation.
+    #[default]
+    Deg0,
+    /// Rotated a quarter turn clockwise, so embedded-graphics' top edge is on the panel's right.
+    Deg90,
+    /// Rotated a half turn, upside down.
+    Deg180,
+    /// Rotated a quarter turn counter-clockwise, so embedded-graphics' top edge is on the panel's
+    /// left.
+    Deg270,
+}
+
+impl Rotation {
+    /// Returns `true` if this rotation swaps width and height.
+    pub(crate) const fn is_sideways(self) -> bool {
+        matches!(self, Self::Deg90 | Self::Deg270)
+    }
+}
+
+impl<C, B> DisplayDriver<C, B> {
+    /// Rotates the embedded-graphics coordinate system on the panel.
+    ///
+    /// Pick the rotation that undoes how the brain is mounted; for example, a brain mounted a
+    /// quarter turn counter-clockwise shows upright content with [`Rotation::Deg90`]. A quarter
+    /// turn swaps the width and height of the driver's [`size`](OriginDimensions), so layout code
+    /// using `bounding_box()` adapts on its own.
+    ///
+    /// The rotation applies within the area left by the [header](Self::with_header_offset),
+    /// [insets](Self::set_insets) and [origin](Self::set_origin), which stay in the panel's own
+    /// orientation. Methods that work in screen coordinates, like
+    /// [`dirty_area`](Self::dirty_area) and [`touch_status`](Self::touch_status), aren't
+    /// rotated either, while [`take_touch`](Self::take_touch) is. Nothing already drawn is
+    /// changed.
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+    }
+
+    /// Returns the current rotation.
+    #[must_use]
+    pub const fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Mirrors everything drawn from now on, for screens viewed through a mirror or from behind.
+    ///
+    /// `horizontal` swaps the panel's left and right edges, and `vertical` swaps its top and
+    /// bottom. Mirroring happens after [rotation](Self::set_rotation), in the panel's own
+    /// orientation, so a horizontal mirror always flips what is seen on the panel left to right,
+    /// however it is rotated. Like rotation, it applies within the drawable area, and to
+    /// [`take_touch`](Self::take_touch) but not to methods that work in screen coordinates.
+    pub fn set_mirror(&mut self, horizontal: bool, vertical: bool) {
+        self.mirror = (horizontal, vertical);
+    }
+
+    /// Returns whether the display is mirrored `(horizontally, vertically)`.
+    #[must_use]
+    pub const fn mirror(&self) -> (bool, bool) {
+        self.mirror
+    }
+
+    /// Returns `true` if the display is rotated or mirrored.
+    pub(crate) fn is_reoriented(&self) -> bool {
+        self.rotation != Rotation::Deg0 || self.mirror != (false, false)
+    }
+
+    /// Maps a point from embedded-graphics coordinates to coordinates relative to the top left
+    /// of an unrotated, unmirrored area of size `area`.
+    pub(crate) const fn orient(&self, point: Point, area: Size) -> Point {
+        let (right, bottom) = (area.width as i32 - 1, area.height as i32 - 1);
+        let rotated = match self.rotation {
+            Rotation::Deg0 => point,
+            Rotation::Deg90 => Point::new(right - point.y, point.x),
+            Rotation::Deg180 => Point::new(right - point.x, bottom - point.y),
+            Rotation::Deg270 => Point::new(point.y, bottom - point.x),
+        };
+
+        self.flip(rotated, area)
+    }
+
+    /// Reverses [`orient`](Self::orient).
+    pub(crate) const fn unorient(&self, point: Point, area: Size) -> Point {
+        let (right, bottom) = (area.width as i32 - 1, area.height as i32 - 1);
+        let point = self.flip(point, area);
+        match self.rotation {
+            Rotation::Deg0 => point,
+            Rotation::Deg90 => Point::new(point.y, right - point.x),
+            Rotation::Deg180 => Point::new(right - point.x, bottom - point.y),
+            Rotation::Deg270 => Point::new(bottom - point.y, point.x),
+        }
+    }
+
+    /// Applies the [mirroring](Self::set_mirror) to a point in an area of size `area`, which
+    /// undoes itself when applied twice.
+    const fn flip(&self, point: Point, area: Size) -> Point {
+        let (horizontal, vertical) = self.mirror;
+        Point::new(
+            if horizontal {
+                area.width as i32 - 1 - point.x
+            } else {
+                point.x
+            },
+            if vertical {
+                area.height as i32 - 1 - point.y
+            } else {
+                point.y
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::{pixelcolor::Rgb888, primitives::Rectangle};
+
+    use super::*;
+    use crate::MockPanel;
+
+    const ROTATIONS: [Rotation; 4] = [
+        Rotation::Deg0,
+        Rotation::Deg90,
+        Rotation::Deg180,
+        Rotation::Deg270,
+    ];
+
+    #[test]
+    fn size_swaps_under_quarter_turns() {
+        let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+        for (rotation, size) in ROTATIONS.into_iter().zip([
+            Size::new(480, 240),
+            Size::new(240, 480),
+            Size::new(480, 240),
+            Size::new(240, 480),
+        ]) {
+            display.set_rotation(rotation);
+            assert_eq!(display.size(), size, "{rotation:?}");
+        }
+        assert_eq!(display.bounding_box().center(), Point::new(119, 239));
+    }
+
+    #[test]
+    fn the_origin_lands_in_the_rotated_corner() {
+        for (rotation, corner) in ROTATIONS.into_iter().zip([
+            Point::new(0, 0),
+            Point::new(479, 0),
+            Point::new(479, 239),
+            Point::new(0, 239),
+        ]) {
+            let mut display = DisplayDriver::new(MockPanel::new());
+            display.set_rotation(rotation);
+
+            display.set_pixel(Point::zero(), Rgb888::RED);
+
+            assert_eq!(
+                display.backend().pixel(corner.x, corner.y),
+                Some(0xFF0000),
+                "{rotation:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn unorient_undoes_orient() {
+        let size = Size::new(480, 240);
+        for rotation in ROTATIONS {
+            for (horizontal, vertical) in
+                [(false, false), (true, false), (false, true), (true, true)]
+            {
+                let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+                display.set_rotation(rotation);
+                display.set_mirror(horizontal, vertical);
+
+                // These points are on screen under every rotation.
+                for point in [Point::zero(), Point::new(12, 34), Point::new(239, 239)] {
+                    let screen = display.orient(point, size);
+                    assert!(Rectangle::new(Point::zero(), size).contains(screen));
+                    assert_eq!(display.unorient(screen, size), point);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Adjustments applied to colors on their way to the panel.
+
+use crate::DisplayDriver;
+
+/// The order of the color channels in the `u32` colors handed to the SDK.
+///
+/// See [`DisplayDriver::set_storage_byte_order`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StorageOrder {
+    /// Red in bits 16-23, green in bits 8-15 and blue in bits 0-7, which is what the VEX SDK
+    /// expects.
+    #[default]
+    Rgb,
+
+    /// Blue in bits 16-23, green in bits 8-15 and red in bits 0-7.
+    Bgr,
+}
+
+impl StorageOrder {
+    /// Converts a color in the display's `0x00RRGGBB` format to this order.
+    pub(crate) const fn apply(self, color: u32) -> u32 {
+        match self {
+            Self::Rgb => color,
+            Self::Bgr => (color & 0x00FF00) | ((color >> 16) & 0xFF) | ((color & 0xFF) << 16),
+        }
+    }
+}
+
+impl<C, B> DisplayDriver<C, B> {
+    /// Sets the channel order of the colors sent to the SDK.
+    ///
+    /// The default, [`StorageOrder::Rgb`], is correct for the VEX SDK, so this should only be
+    /// needed on SDK variants that show red and blue swapped. Everything drawn through the driver
+    /// keeps using the display's `0x00RRGGBB` format, including the shadow buffer and anything
+    /// [saved](Self::write_bmp) from it; the swap only happens right before each SDK call. Content
+    /// that is already on the panel isn't changed.
+    pub fn set_storage_byte_order(&mut self, order: StorageOrder) {
+        self.storage_order = order;
+    }
+
+    /// Returns the channel order of the colors sent to the SDK.
+    #[must_use]
+    pub const fn storage_byte_order(&self) -> StorageOrder {
+        self.storage_order
+    }
+
+    /// Sets how bright everything drawn from now on appears, from `0` (black) to `255` (the
+    /// colors as drawn, which is the default).
+    ///
+    /// The SDK has no control over the panel's backlight, so this dims colors in software on
+    /// their way to the panel instead. It makes the screen less distracting, but doesn't save any
+    /// power. Like the [storage order](Self::set_storage_byte_order), it doesn't affect the
+    /// shadow buffer or what is already on the panel, so redraw the screen for a change to show
+    /// everywhere.
+    pub fn set_brightness(&mut self, level: u8) {
+        self.brightness = level;
+    }
+
+    /// Returns the brightness set by [`set_brightness`](Self::set_brightness).
+    #[must_use]
+    pub const fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Sets the gamma correction applied to everything drawn from now on.
+    ///
+    /// Each color channel is mapped from `c` to `255 * (c / 255) ^ gamma`, so a `gamma` above
+    /// `1.0` darkens the midtones of photos that look washed out on the panel, and one below
+    /// `1.0` brightens them. The default of `1.0` leaves colors as drawn. The mapping is worked
+    /// out once for all 256 channel values whenever the gamma changes, so it only costs a table
+    /// lookup per channel on the way to the panel.
+    ///
+    /// This is a software correction, like the [brightness](Self::set_brightness), which is
+    /// applied after it, so dimming scales the gamma corrected colors. It doesn't affect the
+    /// shadow buffer or what is already on the panel either.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `gamma` isn't a positive, finite number.
+    pub fn set_gamma(&mut self, gamma: f32) {
+        assert!(
+            gamma.is_finite() && gamma > 0.0,
+            "gamma must be a positive, finite number"
+        );
+        self.gamma = gamma;
+        self.gamma_lut = (gamma != 1.0).then(|| {
+            core::array::from_fn(|channel| {
+                ((channel as f32 / 255.0).powf(gamma) * 255.0).round() as u8
+            })
+        });
+    }
+
+    /// Returns the gamma set by [`set_gamma`](Self::set_gamma).
+    #[must_use]
+    pub const fn gamma(&self) -> f32 {
+        self.gamma
+    }
+
+    /// Returns `true` if colors have to be changed on their way to the panel.
+    pub(crate) fn has_color_transform(&self) -> bool {
+        self.storage_order != StorageOrder::Rgb
+            || self.brightness != u8::MAX
+            || self.gamma_lut.is_some()
+    }
+
+    /// Converts a color in the display's native format to what should be sent to the panel.
+    pub(crate) const fn panel_color(&self, color: u32) -> u32 {
+        let color = match &self.gamma_lut {
+            Some(lut) => correct(color, lut),
+            None => color,
+        };
+        self.storage_order.apply(dim(color, self.brightness))
+    }
+}
+
+/// Maps each channel of a `0x00RRGGBB` color through a gamma lookup table.
+const fn correct(color: u32, lut: &[u8; 256]) -> u32 {
+    let [_, r, g, b] = color.to_be_bytes();
+    ((lut[r as usize] as u32) << 16) | ((lut[g as usize] as u32) << 8) | lut[b as usize] as u32
+}
+
+/// Scales each channel of a `0x00RRGGBB` color by `level / 255`, rounding to the nearest value.
+const fn dim(color: u32, level: u8) -> u32 {
+    if level == u8::MAX {
+        return color;
+    }
+
+    let [_, r, g, b] = color.to_be_bytes();
+    (dim_channel(r, level) << 16) | (dim_channel(g, level) << 8) | dim_channel(b, level)
+}
+
+const fn dim_channel(channel: u8, level: u8) -> u32 {
+    (channel as u32 * level as u32 + 127) / 255
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+
+    use super::*;
+    use crate::MockPanel;
+
+    #[test]
+    fn bgr_order_reverses_the_channels() {
+        assert_eq!(StorageOrder::Bgr.apply(0x123456), 0x563412);
+        assert_eq!(StorageOrder::Rgb.apply(0x123456), 0x123456);
+        assert_eq!(StorageOrder::default(), StorageOrder::Rgb);
+    }
+
+    #[test]
+    fn bgr_order_is_applied_to_every_sdk_call() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.enable_shadow_buffer();
+        display.set_storage_byte_order(StorageOrder::Bgr);
+        let color = Rgb888::new(0x12, 0x34, 0x56);
+
+        display.set_pixel(Point::new(0, 0), color);
+        display
+            .fill_solid(&Rectangle::new(Point::new(1, 0), Size::new(2, 2)), color)
+            .unwrap();
+        display.blit(Point::new(3, 0), &[color; 4], 2);
+
+        let panel = display.backend();
+        for x in 0..5 {
+            assert_eq!(panel.pixel(x, 0), Some(0x563412), "pixel {x}");
+        }
+        assert_eq!(display.pixel_at(Point::new(4, 1)), Some(color));
+    }
+
+    #[test]
+    fn the_default_order_sends_colors_unchanged() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        display.set_pixel(Point::new(0, 0), Rgb888::new(0x12, 0x34, 0x56));
+
+        assert_eq!(display.storage_byte_order(), StorageOrder::Rgb);
+        assert_eq!(display.backend().pixel(0, 0), Some(0x123456));
+    }
+}
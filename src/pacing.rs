@@ -0,0 +1,308 @@
+//! Timing frames to the display's refresh rate.
+
+use core::time::Duration;
+use std::time::Instant;
+
+use vexide::{
+    display::Display,
+    time::{sleep, sleep_until},
+};
+
+use crate::DisplayDriver;
+
+impl<C, B> DisplayDriver<C, B> {
+    /// Returns the refresh rate of the V5 panel, in hertz.
+    ///
+    /// The SDK doesn't report the refresh rate, so this is derived from vexide's
+    /// [`Display::REFRESH_INTERVAL`] of about 16.7 ms, which rounds to 60 Hz. Rendering faster than
+    /// this only produces frames that are never shown; see [`FramePacer`] for an easy way not to.
+    #[must_use]
+    pub const fn target_refresh_hz(&self) -> u32 {
+        let interval = Display::REFRESH_INTERVAL.as_micros() as u32;
+        (1_000_000 + interval / 2) / interval
+    }
+
+    /// Returns an estimate of how long it is until the panel's next refresh.
+    ///
+    /// The SDK only exposes vertical sync through [`flush_and_wait`](Self::flush_and_wait), so
+    /// this is approximated from the time of the last render and the panel's
+    /// [`REFRESH_INTERVAL`](Display::REFRESH_INTERVAL), assuming the render happened right on a
+    /// refresh. That holds closely after `flush_and_wait`, which returns just after one, and
+    /// roughly after a plain [`render`](Self::render). Before the first render there is nothing
+    /// to go on, and this returns zero.
+    #[must_use]
+    pub fn time_until_vsync(&self) -> Duration {
+        let Some(last_render) = self.last_render else {
+            return Duration::ZERO;
+        };
+
+        let interval = Display::REFRESH_INTERVAL.as_nanos();
+        let into_frame = last_render.elapsed().as_nanos() % interval;
+        Duration::from_nanos((interval - into_frame) as u64)
+    }
+
+    /// Waits until the panel's next refresh, as estimated by
+    /// [`time_until_vsync`](Self::time_until_vsync).
+    ///
+    /// Starting to draw a frame right after a refresh gives it the most time to be finished
+    /// before the next one. Unlike `flush_and_wait`, this doesn't render anything, and it sleeps
+    /// in every render mode, so other tasks keep running.
+    pub async fn wait_for_vsync(&self) {
+        sleep(self.time_until_vsync()).await;
+    }
+}
+
+/// Spaces out frames so that a render loop runs at a steady rate.
+///
+/// Call [`wait`](Self::wait) once per frame, and it will sleep until the next frame is due. Frames
+/// are scheduled from when the previous one was due rather than from when `wait` was called, so
+/// the time spent drawing doesn't slow the loop down. If a frame takes so long that the next one
+/// is already late, the schedule restarts from the current time instead of trying to catch up,
+/// and the skipped frames are counted in [`dropped_frames`](Self::dropped_frames).
+///
+/// # Examples
+///
+/// ```
+/// # use vexide::prelude::*;
+/// use vexide_embedded_graphics::{DisplayDriver, FramePacer};
+///
+/// # #[vexide::main]
+/// # async fn main(peripherals: Peripherals) {
+/// let mut display = DisplayDriver::new(peripherals.display);
+/// let mut pacer = FramePacer::native();
+///
+/// loop {
+///     // Draw the frame...
+///     display.render();
+///     pacer.wait().await;
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FramePacer<K = SystemClock> {
+    period: Duration,
+    next_frame: Option<Instant>,
+    dropped_frames: u64,
+    clock: K,
+}
+
+/// A source of time for a [`FramePacer`].
+///
+/// This exists so that pacing can be tested with a clock that is advanced by hand. A pacer both
+/// reads the time and sleeps through its clock, so a test clock can make
+/// [`wait`](FramePacer::wait) return right away by jumping to the deadline. Everything else
+/// should use [`SystemClock`].
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> Instant;
+
+    /// Waits until `deadline`, as measured by [`now`](Self::now).
+    fn sleep_until(&self, deadline: Instant) -> impl Future<Output = ()>;
+}
+
+/// The real system clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> impl Future<Output = ()> {
+        sleep_until(deadline)
+    }
+}
+
+impl FramePacer {
+    /// Creates a pacer that starts a new frame every `period`.
+    #[must_use]
+    pub const fn new(period: Duration) -> Self {
+        Self::with_clock(period, SystemClock)
+    }
+
+    /// Creates a pacer that runs at `hz` frames per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hz` is zero.
+    #[must_use]
+    pub fn from_hz(hz: u32) -> Self {
+        assert!(hz > 0, "frame rate must be greater than zero");
+        Self::new(Duration::from_secs(1) / hz)
+    }
+
+    /// Creates a pacer that runs at the panel's native refresh rate.
+    ///
+    /// See [`DisplayDriver::target_refresh_hz`].
+    #[must_use]
+    pub const fn native() -> Self {
+        Self::new(Display::REFRESH_INTERVAL)
+    }
+}
+
+impl<K: Clock> FramePacer<K> {
+    /// Creates a pacer that starts a new frame every `period`, reading the time from `clock`.
+    #[must_use]
+    pub const fn with_clock(period: Duration, clock: K) -> Self {
+        Self {
+            period,
+            next_frame: None,
+            dropped_frames: 0,
+            clock,
+        }
+    }
+
+    /// Returns the time between frames.
+    #[must_use]
+    pub const fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Returns the number of frames that were due but missed because [`wait`](Self::wait) was
+    /// called too late.
+    ///
+    /// A frame that overruns by several periods counts each of the frames it skipped over. A
+    /// steadily climbing count means the loop is doing more work per frame than the frame rate
+    /// allows, or something else is keeping it from running.
+    #[must_use]
+    pub const fn dropped_frames(&self) -> u64 {
+        self.dropped_frames
+    }
+
+    /// Resets the [dropped frame](Self::dropped_frames) count to zero.
+    pub fn reset_dropped_frames(&mut self) {
+        self.dropped_frames = 0;
+    }
+
+    /// Forgets when the next frame is due, so the next [`wait`](Self::wait) waits a full period.
+    pub fn reset(&mut self) {
+        self.next_frame = None;
+    }
+
+    /// Waits until the next frame is due.
+    ///
+    /// The first call after the pacer is created or [reset](Self::reset) waits one full period.
+    pub async fn wait(&mut self) {
+        let now = self.clock.now();
+        let deadline = self.next_frame.unwrap_or(now + self.period);
+
+        if deadline >= now {
+            self.clock.sleep_until(deadline).await;
+            self.next_frame = Some(deadline + self.period);
+        } else {
+            let late = (now - deadline).as_nanos() / self.period.as_nanos().max(1);
+            self.dropped_frames += late as u64 + 1;
+            self.next_frame = Some(now + self.period);
+        }
+    }
+}
+
+impl Default for FramePacer {
+    fn default() -> Self {
+        Self::native()
+    }
+}
+
+/// A clock for tests that only moves when it is told to, or when something sleeps on it.
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub(crate) struct ManualClock {
+    now: core::cell::Cell<Instant>,
+}
+
+#[cfg(test)]
+impl ManualClock {
+    pub(crate) fn new() -> Self {
+        Self {
+            now: core::cell::Cell::new(Instant::now()),
+        }
+    }
+
+    pub(crate) fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+#[cfg(test)]
+impl Clock for &ManualClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> impl Future<Output = ()> {
+        self.now.set(self.now.get().max(deadline));
+        core::future::ready(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use super::*;
+    use crate::MockPanel;
+
+    /// Runs a [`FramePacer::wait`] on a manual clock, which never has to wait for real.
+    fn wait(pacer: &mut FramePacer<&ManualClock>) {
+        let poll = pin!(pacer.wait()).poll(&mut Context::from_waker(Waker::noop()));
+        assert_eq!(poll, Poll::Ready(()));
+    }
+
+    #[test]
+    fn the_native_pacer_runs_at_the_panel_refresh_rate() {
+        let display = DisplayDriver::new(MockPanel::new());
+        let hz = display.target_refresh_hz();
+
+        assert_eq!(hz, 60);
+        assert_eq!(FramePacer::native().period(), Display::REFRESH_INTERVAL);
+        assert_eq!(
+            FramePacer::default().period(),
+            FramePacer::native().period()
+        );
+
+        let from_hz = FramePacer::from_hz(hz).period();
+        assert!(from_hz.abs_diff(Display::REFRESH_INTERVAL) < Duration::from_millis(1));
+    }
+
+    #[test]
+    fn frames_are_scheduled_from_when_they_were_due() {
+        let clock = ManualClock::new();
+        let start = clock.now.get();
+        let mut pacer = FramePacer::with_clock(Duration::from_millis(10), &clock);
+
+        wait(&mut pacer);
+        assert_eq!(clock.now.get() - start, Duration::from_millis(10));
+
+        // Drawing the frame took 4 ms, which comes out of the wait for the next one.
+        clock.advance(Duration::from_millis(4));
+        wait(&mut pacer);
+        assert_eq!(clock.now.get() - start, Duration::from_millis(20));
+        assert_eq!(pacer.dropped_frames(), 0);
+    }
+
+    #[test]
+    fn overruns_count_every_frame_they_skip() {
+        let clock = ManualClock::new();
+        let start = clock.now.get();
+        let mut pacer = FramePacer::with_clock(Duration::from_millis(10), &clock);
+        wait(&mut pacer);
+
+        // The frames due at 20, 30 and 40 ms are all missed.
+        clock.advance(Duration::from_millis(35));
+        wait(&mut pacer);
+        assert_eq!(pacer.dropped_frames(), 3);
+        assert_eq!(clock.now.get() - start, Duration::from_millis(45));
+
+        // The schedule restarts from the late frame instead of trying to catch up.
+        wait(&mut pacer);
+        assert_eq!(clock.now.get() - start, Duration::from_millis(55));
+        assert_eq!(pacer.dropped_frames(), 3);
+
+        pacer.reset_dropped_frames();
+        assert_eq!(pacer.dropped_frames(), 0);
+    }
+}
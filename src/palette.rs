@@ -0,0 +1,122 @@
+//! Drawing indexed images through a palette.
+
+use embedded_graphics_core::{
+    pixelcolor::{Gray8, GrayColor},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use crate::{DisplayDriver, DisplayError, PanelBackend, ToDisplayStorage};
+
+/// A draw target that looks colors up in a palette, for images with only a few distinct colors.
+///
+/// The target's color is [`Gray8`], but its luma is read as an index into the palette rather
+/// than a shade of gray, so an indexed sprite can be stored as an
+/// [`ImageRaw<Gray8>`](embedded_graphics::image::ImageRaw) with one byte per pixel. Each palette
+/// entry is converted to the display's format once, when the target is created, instead of once
+/// per pixel. Indices past the end of the palette are drawn black.
+///
+/// Created with [`DisplayDriver::with_palette`].
+///
+/// # Examples
+///
+/// ```
+/// # use vexide::prelude::*;
+/// use embedded_graphics::{
+///     image::{Image, ImageRaw},
+///     pixelcolor::{Gray8, Rgb888},
+///     prelude::*,
+/// };
+/// use vexide_embedded_graphics::DisplayDriver;
+///
+/// const PALETTE: [Rgb888; 8] = [
+///     Rgb888::BLACK,
+///     Rgb888::RED,
+///     Rgb888::GREEN,
+///     Rgb888::BLUE,
+///     Rgb888::YELLOW,
+///     Rgb888::CYAN,
+///     Rgb888::MAGENTA,
+///     Rgb888::WHITE,
+/// ];
+/// const SPRITE: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 7, 6, 5, 4, 3, 2, 1, 0];
+///
+/// # #[vexide::main]
+/// # async fn main(peripherals: Peripherals) {
+/// let mut display = DisplayDriver::new(peripherals.display);
+/// let sprite = ImageRaw::<Gray8>::new(&SPRITE, 8);
+///
+/// Image::new(&sprite, Point::new(20, 20))
+///     .draw(&mut display.with_palette(&PALETTE))
+///     .unwrap();
+/// # }
+/// ```
+pub struct PaletteDrawTarget<'a, C, B: PanelBackend> {
+    driver: &'a mut DisplayDriver<C, B>,
+    palette: Vec<u32>,
+}
+
+impl<C: ToDisplayStorage, B: PanelBackend> DisplayDriver<C, B> {
+    /// Returns a draw target that draws indices into `palette`.
+    ///
+    /// See [`PaletteDrawTarget`].
+    pub fn with_palette(&mut self, palette: &[C]) -> PaletteDrawTarget<'_, C, B> {
+        PaletteDrawTarget {
+            palette: palette
+                .iter()
+                .map(|color| color.to_display_storage())
+                .collect(),
+            driver: self,
+        }
+    }
+}
+
+impl<C, B: PanelBackend> OriginDimensions for PaletteDrawTarget<'_, C, B> {
+    fn size(&self) -> Size {
+        self.driver.size()
+    }
+}
+
+impl<C, B: PanelBackend> DrawTarget for PaletteDrawTarget<'_, C, B> {
+    type Color = Gray8;
+
+    type Error = DisplayError;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(pos, index) in pixels {
+            if let Some(pos) = self.driver.to_screen(pos) {
+                self.driver.write_pixel(pos, lookup(&self.palette, index));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let palette = &self.palette;
+        let colors = colors.into_iter().map(|index| lookup(palette, index));
+        if self.driver.fill_contiguous_storage(area, colors) {
+            Ok(())
+        } else {
+            Err(DisplayError::ColorBufferTooSmall)
+        }
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.driver
+            .fill_solid_storage(area, lookup(&self.palette, color));
+
+        Ok(())
+    }
+}
+
+/// Returns the palette entry for `index`, or black if it is past the end of the palette.
+fn lookup(palette: &[u32], index: Gray8) -> u32 {
+    palette.get(usize::from(index.luma())).copied().unwrap_or(0)
+}
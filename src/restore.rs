@@ -0,0 +1,89 @@
+//! Saving part of the screen so it can be put back later.
+
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use crate::{DisplayDriver, PanelBackend, frame::safe_intersection};
+
+/// A saved copy of part of the screen, for un-drawing something that moves over a static
+/// background.
+///
+/// [Capture](Self::capture) the area under a HUD or cursor before drawing it, and
+/// [restore](Self::restore) it before drawing the next frame's, instead of redrawing the whole
+/// background. The copy is read from the [shadow
+/// buffer](DisplayDriver::enable_shadow_buffer), since the panel can't be read back, so nothing
+/// is captured while it is disabled. The memory for the copy is kept between captures, so
+/// capturing an area of the same size every frame doesn't allocate.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics_core::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+/// use vexide_embedded_graphics::{DisplayDriver, MockPanel, RestoreRegion};
+///
+/// let mut display = DisplayDriver::new(MockPanel::new());
+/// display.enable_shadow_buffer();
+/// display.clear(Rgb888::BLUE).unwrap();
+///
+/// let cursor = Rectangle::new(Point::new(10, 10), Size::new(4, 4));
+/// let mut under_cursor = RestoreRegion::new();
+/// under_cursor.capture(&display, &cursor);
+/// display.fill_solid(&cursor, Rgb888::WHITE).unwrap();
+///
+/// under_cursor.restore(&mut display);
+/// assert_eq!(display.backend().pixel(12, 12), Some(0x0000FF));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RestoreRegion {
+    area: Option<Rectangle>,
+    pixels: Vec<u32>,
+}
+
+impl RestoreRegion {
+    /// Creates a region with nothing captured yet.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            area: None,
+            pixels: Vec::new(),
+        }
+    }
+
+    /// Returns the area that was captured, or `None` if nothing was.
+    ///
+    /// This is the captured area clipped to the drawable area, in embedded-graphics coordinates.
+    #[must_use]
+    pub const fn area(&self) -> Option<Rectangle> {
+        self.area
+    }
+
+    /// Saves what is currently shown in `area` of `display`, replacing anything captured before.
+    ///
+    /// Only the part of `area` inside the drawable area is saved. Nothing is saved if that is
+    /// empty, or if the shadow buffer is disabled.
+    pub fn capture<C, B: PanelBackend>(&mut self, display: &DisplayDriver<C, B>, area: &Rectangle) {
+        self.area = None;
+        self.pixels.clear();
+
+        let visible = safe_intersection(area, &display.bounding_box());
+        if visible.is_zero_sized() || !display.has_shadow_buffer() {
+            return;
+        }
+
+        self.pixels.extend(
+            visible
+                .points()
+                .map(|point| display.stored_pixel(point).unwrap_or_default()),
+        );
+        self.area = Some(visible);
+    }
+
+    /// Draws the captured pixels back onto `display` where they were captured from.
+    ///
+    /// The capture is kept, so it can be restored again. This does nothing if nothing was
+    /// captured.
+    pub fn restore<C, B: PanelBackend>(&self, display: &mut DisplayDriver<C, B>) {
+        if let Some(area) = self.area {
+            display.fill_contiguous_storage(&area, self.pixels.iter().copied());
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! A log pane that scrolls up as lines are added.
+
+use std::collections::VecDeque;
+
+use embedded_graphics::{
+    mono_font::MonoTextStyle,
+    prelude::*,
+    primitives::Rectangle,
+    text::{Baseline, Text},
+};
+
+use crate::{
+    DisplayDriver, DisplayError, PanelBackend, ToDisplayStorage, WidgetStyle,
+    frame::safe_intersection,
+};
+
+/// A block of text that scrolls up as new lines arrive, like a log.
+///
+/// With the [shadow buffer](DisplayDriver::enable_shadow_buffer) enabled, drawing only touches
+/// what changed: lines already on screen are moved up by copying them in the shadow buffer, and
+/// only the new lines at the bottom are drawn. Without it, every line is drawn again whenever the
+/// text scrolls. Only as many lines as fit in the area are kept, and lines too long for it are
+/// cut off.
+#[derive(Debug, Clone)]
+pub struct ScrollingText<C> {
+    area: Rectangle,
+    style: WidgetStyle<C>,
+    lines: VecDeque<String>,
+    shown: usize,
+    pending: usize,
+    drawn: bool,
+}
+
+impl<C: PixelColor> ScrollingText<C> {
+    /// Creates an empty log pane covering `area`.
+    #[must_use]
+    pub fn new(area: Rectangle, style: WidgetStyle<C>) -> Self {
+        Self {
+            area,
+            style,
+            lines: VecDeque::new(),
+            shown: 0,
+            pending: 0,
+            drawn: false,
+        }
+    }
+
+    /// Returns the area the text is drawn in.
+    #[must_use]
+    pub const fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// Adds a line at the bottom, to be shown on the next [`draw`](Self::draw).
+    pub fn push_line(&mut self, line: &str) {
+        let capacity = self.capacity();
+        if capacity == 0 {
+            return;
+        }
+
+        if self.lines.len() == capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line.to_owned());
+        self.pending = (self.pending + 1).min(capacity);
+    }
+
+    /// Removes every line, clearing the area on the next [`draw`](Self::draw).
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.shown = 0;
+        self.pending = 0;
+        self.drawn = false;
+    }
+
+    /// Returns the number of lines that fit in the area.
+    fn capacity(&self) -> usize {
+        (self.area.size.height / self.line_height()) as usize
+    }
+
+    fn line_height(&self) -> u32 {
+        self.style.font.character_size.height.max(1)
+    }
+}
+
+impl<C: ToDisplayStorage> ScrollingText<C> {
+    /// Draws the lines added since the last draw, scrolling older lines up to make room.
+    ///
+    /// The first draw, and the first after [`clear`](Self::clear), clears the whole area to the
+    /// style's background instead. Scrolling needs the
+    /// [shadow buffer](DisplayDriver::enable_shadow_buffer) to move the old lines up; if it isn't
+    /// enabled, every line is drawn again instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from drawing the text to `display`.
+    pub fn draw<B: PanelBackend>(
+        &mut self,
+        display: &mut DisplayDriver<C, B>,
+    ) -> Result<(), DisplayError> {
+        let capacity = self.capacity();
+        let line_height = self.line_height();
+
+        let first_new = if self.drawn {
+            let overflow = (self.shown + self.pending).saturating_sub(capacity);
+            if overflow == 0 {
+                self.shown
+            } else if display.has_shadow_buffer() {
+                display.scroll_up(&self.area, overflow as u32 * line_height);
+                self.shown - overflow
+            } else {
+                0
+            }
+        } else {
+            display.viewport(self.area).fill_solid(
+                &Rectangle::new(Point::zero(), self.area.size),
+                self.style.background,
+            )?;
+            self.drawn = true;
+            0
+        };
+        self.shown = self.lines.len();
+        self.pending = 0;
+
+        let mut viewport = display.viewport(self.area);
+        let text_style = MonoTextStyle::new(self.style.font, self.style.foreground);
+        for (row, line) in self.lines.iter().enumerate().skip(first_new) {
+            let top = Point::new(0, (row as u32 * line_height) as i32);
+            viewport.fill_solid(
+                &Rectangle::new(top, Size::new(self.area.size.width, line_height)),
+                self.style.background,
+            )?;
+            Text::with_baseline(line, top, text_style, Baseline::Top).draw(&mut viewport)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Moves everything in `area`, in embedded-graphics coordinates, up by `rows`, reading it back
+    /// from the shadow buffer. The bottom `rows` of the area are left as they were.
+    ///
+    /// Each row is copied on its own through the scratch buffer. Rows are moved top to bottom, so
+    /// every row is read before it is overwritten.
+    ///
+    /// Does nothing if the shadow buffer is disabled.
+    pub(crate) fn scroll_up(&mut self, area: &Rectangle, rows: u32) {
+        if self.shadow.is_none() || rows >= area.size.height {
+            return;
+        }
+
+        let target = safe_intersection(
+            &Rectangle::new(
+                area.top_left,
+                Size::new(area.size.width, area.size.height - rows),
+            ),
+            &self.bounding_box(),
+        );
+        let width = target.size.width as usize;
+        for y in target.rows() {
+            for (index, x) in target.columns().enumerate() {
+                self.buffer[index] = self
+                    .stored_pixel(Point::new(x, y + rows as i32))
+                    .unwrap_or(0);
+            }
+            self.write_row(Point::new(target.top_left.x, y), width as u32);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::pixelcolor::Rgb888;
+
+    use super::*;
+    use crate::{MockPanel, Rotation};
+
+    const STYLE: WidgetStyle<Rgb888> = WidgetStyle::new(Rgb888::WHITE, Rgb888::BLUE);
+
+    /// A pane three lines tall of the 6x10 font.
+    const AREA: Rectangle = Rectangle::new(Point::new(20, 30), Size::new(60, 30));
+
+    /// Draws `lines` into a fresh pane in one go, for comparing against a pane that scrolled.
+    fn drawn_at_once(lines: &[&str]) -> DisplayDriver<Rgb888, MockPanel> {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let mut text = ScrollingText::new(AREA, STYLE);
+        for line in lines {
+            text.push_line(line);
+        }
+        text.draw(&mut display).unwrap();
+        display
+    }
+
+    /// Draws each of `lines` into a pane on its own, so the pane scrolls once it is full.
+    fn drawn_one_by_one(
+        mut display: DisplayDriver<Rgb888, MockPanel>,
+    ) -> DisplayDriver<Rgb888, MockPanel> {
+        let mut text = ScrollingText::new(AREA, STYLE);
+        for line in ["one", "two", "three", "four", "five"] {
+            text.push_line(line);
+            text.draw(&mut display).unwrap();
+        }
+        display
+    }
+
+    #[test]
+    fn draw_does_not_enable_the_shadow_buffer() {
+        let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+        let mut text = ScrollingText::new(AREA, STYLE);
+        text.push_line("one");
+
+        text.draw(&mut display).unwrap();
+
+        assert!(!display.has_shadow_buffer());
+    }
+
+    #[test]
+    fn scrolling_without_the_shadow_buffer_draws_every_line_again() {
+        let display = drawn_one_by_one(DisplayDriver::new(MockPanel::new()));
+
+        let expected = drawn_at_once(&["three", "four", "five"]);
+        assert!(
+            display.backend().framebuffer() == expected.backend().framebuffer(),
+            "the scrolled pane should show the last three lines"
+        );
+    }
+
+    #[test]
+    fn scrolling_with_the_shadow_buffer_moves_the_old_lines_up() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.enable_shadow_buffer();
+        let display = drawn_one_by_one(display);
+
+        let expected = drawn_at_once(&["three", "four", "five"]);
+        assert!(
+            display.backend().framebuffer() == expected.backend().framebuffer(),
+            "the scrolled pane should show the last three lines"
+        );
+    }
+
+    #[test]
+    fn scroll_up_moves_pixels_and_leaves_the_bottom_rows() {
+        let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+        display.enable_shadow_buffer();
+        let area = Rectangle::new(Point::new(0, 0), Size::new(10, 10));
+        display.set_pixel(Point::new(2, 5), Rgb888::RED);
+        display.set_pixel(Point::new(3, 9), Rgb888::GREEN);
+
+        display.scroll_up(&area, 3);
+
+        assert_eq!(display.backend().pixel(2, 2), Some(0xFF0000));
+        assert_eq!(display.backend().pixel(2, 5), Some(0));
+        assert_eq!(display.backend().pixel(3, 6), Some(0x00FF00));
+        assert_eq!(display.backend().pixel(3, 9), Some(0x00FF00));
+    }
+
+    #[test]
+    fn scroll_up_follows_the_rotation() {
+        let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+        display.enable_shadow_buffer();
+        display.set_rotation(Rotation::Deg180);
+        let area = Rectangle::new(Point::new(0, 0), Size::new(10, 10));
+        display.set_pixel(Point::new(2, 5), Rgb888::RED);
+
+        display.scroll_up(&area, 3);
+
+        let mut expected = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+        expected.set_rotation(Rotation::Deg180);
+        expected.set_pixel(Point::new(2, 2), Rgb888::RED);
+        expected.set_pixel(Point::new(2, 5), Rgb888::BLACK);
+        assert!(
+            display.backend().framebuffer() == expected.backend().framebuffer(),
+            "the pixel should move up in rotated coordinates"
+        );
+    }
+}
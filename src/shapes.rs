@@ -0,0 +1,659 @@
+//! Accelerated drawing of filled shapes and lines.
+
+use core::f32::consts::TAU;
+
+use embedded_graphics::geometry::Angle;
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use crate::{DisplayDriver, PanelBackend, ToDisplayStorage, frame::safe_intersection};
+
+impl<C: ToDisplayStorage, B: PanelBackend> DisplayDriver<C, B> {
+    /// Sets the pixel at `point` to `color`. Pixels outside of the drawable area are ignored.
+    ///
+    /// This is the same as drawing a single [`Pixel`], without going through an iterator, which
+    /// makes it handy in procedural drawing loops. It takes the driver's color type, so it is
+    /// also used instead of [`DrawSurface::set_pixel`](crate::DrawSurface::set_pixel) when that
+    /// trait is in scope; on an [`Rgb888`](embedded_graphics_core::pixelcolor::Rgb888) driver
+    /// both do the same thing.
+    pub fn set_pixel(&mut self, point: Point, color: C) {
+        if let Some(point) = self.to_screen(point) {
+            self.write_pixel(point, color.to_display_storage());
+        }
+    }
+
+    /// Fills the triangle with corners `p0`, `p1` and `p2`.
+    ///
+    /// embedded-graphics draws filled triangles a pixel at a time, which is slow for large shapes
+    /// like arrows and gauge needles. This instead sends each row of the triangle to the display as
+    /// a single solid fill. The corners may be given in any order, and all of the triangle's edges
+    /// are included in the fill.
+    pub fn fill_triangle(&mut self, p0: Point, p1: Point, p2: Point, color: C) {
+        self.fill_triangle_storage(p0, p1, p2, color.to_display_storage());
+    }
+
+    /// Fills a circle around `center`.
+    ///
+    /// Like [`fill_triangle`](Self::fill_triangle), each row is sent to the display as a single
+    /// solid fill. The circle is stretched horizontally by the [pixel
+    /// aspect](Self::set_pixel_aspect) so that it looks round on non-square pixels, which means
+    /// `radius` is measured in rows.
+    pub fn fill_circle(&mut self, center: Point, radius: u32, color: C) {
+        self.draw_ellipse_storage(center, radius, None, None, color.to_display_storage());
+    }
+
+    /// Draws the outline of a circle around `center`, `stroke_width` pixels thick.
+    ///
+    /// The outline lies inside `radius`, and is corrected for the [pixel
+    /// aspect](Self::set_pixel_aspect) like [`fill_circle`](Self::fill_circle). Each row of the
+    /// outline is sent to the display as at most two solid fills.
+    pub fn draw_circle(&mut self, center: Point, radius: u32, stroke_width: u32, color: C) {
+        self.draw_ellipse_storage(
+            center,
+            radius,
+            Some(stroke_width),
+            None,
+            color.to_display_storage(),
+        );
+    }
+
+    /// Draws part of the outline of a circle around `center`, `stroke_width` pixels thick.
+    ///
+    /// The arc starts at `angle_start` and covers `angle_sweep`, with the same conventions as
+    /// embedded-graphics' `Arc`: `0°` points right and positive angles go clockwise. It lies inside
+    /// `radius` and is corrected for the [pixel aspect](Self::set_pixel_aspect), like
+    /// [`draw_circle`](Self::draw_circle), and each run of the arc on a row is sent to the display
+    /// as a single solid fill, rather than a pixel at a time like a styled `Arc`. A sweep of a
+    /// full turn or more draws the whole outline, and nothing is drawn for a sweep of zero.
+    pub fn draw_arc(
+        &mut self,
+        center: Point,
+        radius: u32,
+        stroke_width: u32,
+        angle_start: Angle,
+        angle_sweep: Angle,
+        color: C,
+    ) {
+        let sweep = angle_sweep.to_radians();
+        if sweep == 0.0 || !sweep.is_finite() {
+            return;
+        }
+
+        // Arcs are walked clockwise, so one that sweeps counterclockwise starts at its end.
+        let start = angle_start.to_radians() + sweep.min(0.0);
+        let range = AngleRange {
+            start: start.rem_euclid(TAU),
+            sweep: sweep.abs(),
+        };
+        self.draw_ellipse_storage(
+            center,
+            radius,
+            Some(stroke_width),
+            Some(range),
+            color.to_display_storage(),
+        );
+    }
+
+    /// Draws a straight line from `start` to `end`, both included, `stroke_width` pixels thick.
+    ///
+    /// A styled embedded-graphics `Line` is drawn as a stream of single pixels, which only gets
+    /// combined into fewer calls where it runs left to right along a row. This walks the line
+    /// with Bresenham's algorithm instead, and sends each run of pixels in the same row (or
+    /// column, for lines that are closer to vertical) to the display as a single solid fill, so
+    /// lines close to horizontal or vertical take only a few calls in either direction. A one
+    /// pixel wide line covers the same pixels as an embedded-graphics one. Thick lines widen each
+    /// run across the line's main direction, which makes diagonal lines look a little thinner
+    /// than `stroke_width`. Nothing is drawn if `stroke_width` is zero.
+    pub fn draw_line(&mut self, start: Point, end: Point, stroke_width: u32, color: C) {
+        self.draw_line_storage(start, end, stroke_width, color.to_display_storage());
+    }
+
+    /// Draws a grid of one pixel wide lines every `spacing` pixels, starting at `(0, 0)`, over
+    /// the whole drawable area.
+    ///
+    /// This is a layout aid: the grid is drawn once, over whatever is already there, so draw it
+    /// last in each frame that should show it, behind whatever debug flag suits. Like all other
+    /// drawing, it's offset past the header and follows the [origin](Self::set_origin) and
+    /// [insets](Self::set_insets), so the lines fall on the coordinates used for layout.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `spacing` is zero.
+    pub fn draw_debug_grid(&mut self, spacing: u32, color: C) {
+        assert!(spacing > 0, "grid spacing must be greater than zero");
+        let color = color.to_display_storage();
+        let size = self.size();
+
+        for x in (0..size.width).step_by(spacing as usize) {
+            let line = Rectangle::new(Point::new(x as i32, 0), Size::new(1, size.height));
+            self.fill_solid_storage(&line, color);
+        }
+        for y in (0..size.height).step_by(spacing as usize) {
+            let line = Rectangle::new(Point::new(0, y as i32), Size::new(size.width, 1));
+            self.fill_solid_storage(&line, color);
+        }
+    }
+
+    /// Fills `area` with a checkerboard of `cell` by `cell` squares, alternating between
+    /// `color_a` and `color_b`.
+    ///
+    /// The squares are laid out from the top left corner of `area`, which gets `color_a`, and the
+    /// ones along the right and bottom edges are cut short if `area` isn't a whole number of
+    /// cells. Each visible square is sent to the display as a single solid fill, which makes this
+    /// a cheap transparency placeholder or test pattern.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell` is zero.
+    pub fn fill_checkerboard(&mut self, area: &Rectangle, color_a: C, color_b: C, cell: u32) {
+        assert!(cell > 0, "checkerboard cell size must be greater than zero");
+        let colors = [color_a.to_display_storage(), color_b.to_display_storage()];
+        let visible = safe_intersection(area, &self.bounding_box());
+        if visible.is_zero_sized() {
+            return;
+        }
+
+        // Only visit the cells that overlap the screen, so large off-screen areas stay cheap.
+        let offset = (
+            visible.top_left.x.abs_diff(area.top_left.x),
+            visible.top_left.y.abs_diff(area.top_left.y),
+        );
+        let first = (offset.0 / cell, offset.1 / cell);
+        let last = (
+            (offset.0 + visible.size.width - 1) / cell,
+            (offset.1 + visible.size.height - 1) / cell,
+        );
+        for row in first.1..=last.1 {
+            for column in first.0..=last.0 {
+                // The cells visited overlap the screen, so their corners fit in an `i32` even when
+                // the corner of `area` is far off screen.
+                let top_left = Point::new(
+                    area.top_left.x.wrapping_add_unsigned(column * cell),
+                    area.top_left.y.wrapping_add_unsigned(row * cell),
+                );
+                let square = Rectangle::new(top_left, Size::new(cell, cell)).intersection(&visible);
+                self.fill_solid_storage(&square, colors[((row + column) % 2) as usize]);
+            }
+        }
+    }
+
+    /// Fills the area around `seed` that is the same color as `seed` with `color`, like a paint
+    /// bucket.
+    ///
+    /// The area is found by reading the [shadow buffer](Self::enable_shadow_buffer), so this does
+    /// nothing if it isn't enabled, or if `seed` is outside of the drawable area. Pixels count as
+    /// connected if they share an edge, and the fill never leaves the drawable area. Each row of
+    /// the area is sent to the display as a single solid fill.
+    pub fn flood_fill(&mut self, seed: Point, color: C) {
+        self.flood_fill_storage(seed, color.to_display_storage());
+    }
+}
+
+impl<C, B> DisplayDriver<C, B> {
+    /// Sets how non-square pixels are corrected for by [`fill_circle`](Self::fill_circle) and
+    /// [`draw_circle`](Self::draw_circle).
+    ///
+    /// `horizontal` pixels across cover the same physical distance as `vertical` pixels down. The
+    /// default of 1:1 is correct for the V5 panel's square pixels, but a different aspect is
+    /// useful when what's drawn is later shown scaled unevenly. For example, an aspect of 2:1
+    /// makes circles twice as many pixels wide as they are tall.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either part of the aspect is zero.
+    pub fn set_pixel_aspect(&mut self, horizontal: u32, vertical: u32) {
+        assert!(
+            horizontal > 0 && vertical > 0,
+            "pixel aspect must be greater than zero"
+        );
+        self.pixel_aspect = (horizontal, vertical);
+    }
+
+    /// Returns the pixel aspect as `(horizontal, vertical)`.
+    ///
+    /// See [`set_pixel_aspect`](Self::set_pixel_aspect).
+    #[must_use]
+    pub const fn pixel_aspect(&self) -> (u32, u32) {
+        self.pixel_aspect
+    }
+
+    /// Converts a vertical distance to the horizontal distance that looks the same length.
+    fn aspect_width(&self, height: u32) -> u32 {
+        let (horizontal, vertical) = self.pixel_aspect;
+        ((u64::from(height) * u64::from(horizontal) + u64::from(vertical) / 2)
+            / u64::from(vertical)) as u32
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Fills a triangle, in embedded-graphics coordinates, with a native color.
+    pub(crate) fn fill_triangle_storage(&mut self, p0: Point, p1: Point, p2: Point, color: u32) {
+        let height = self.size().height as i32;
+        let top = p0.y.min(p1.y).min(p2.y).max(0);
+        let bottom = p0.y.max(p1.y).max(p2.y).min(height - 1);
+
+        for y in top..=bottom {
+            let Some((left, right)) = triangle_span([p0, p1, p2], y) else {
+                continue;
+            };
+            self.fill_row_storage(y, left.into(), right.into(), color);
+        }
+    }
+
+    /// Fills columns `start` to `end` of row `y`, in embedded-graphics coordinates, with a native
+    /// color.
+    ///
+    /// The columns are clipped to the drawable area first, so spans of shapes that reach far off
+    /// screen don't overflow.
+    fn fill_row_storage(&mut self, y: i32, start: i64, end: i64, color: u32) {
+        let (start, end) = (start.max(0), end.min(i64::from(self.size().width) - 1));
+        if start <= end {
+            let span = Rectangle::new(
+                Point::new(start as i32, y),
+                Size::new((end - start + 1) as u32, 1),
+            );
+            self.fill_solid_storage(&span, color);
+        }
+    }
+
+    /// Draws a line, in embedded-graphics coordinates, with a native color.
+    pub(crate) fn draw_line_storage(
+        &mut self,
+        start: Point,
+        end: Point,
+        stroke_width: u32,
+        color: u32,
+    ) {
+        if stroke_width == 0 {
+            return;
+        }
+
+        // Walk along x, swapping the axes of lines that are closer to vertical.
+        let steep = end.y.abs_diff(start.y) > end.x.abs_diff(start.x);
+        let transpose = |point: Point| {
+            if steep {
+                Point::new(point.y, point.x)
+            } else {
+                point
+            }
+        };
+        let (from, to) = (transpose(start), transpose(end));
+
+        // The walk is done in `i64` so that lines reaching far off screen can't overflow. Run
+        // corners are pulled in to just outside the screen before filling, which doesn't change
+        // what is visible.
+        let clamped = |x: i64, y: i64| {
+            let limit = i64::from(u16::MAX);
+            Point::new(x.clamp(-1, limit) as i32, y.clamp(-1, limit) as i32)
+        };
+        let dx = i64::from(to.x.abs_diff(from.x));
+        let dy = i64::from(to.y.abs_diff(from.y));
+        let step = (to.x.cmp(&from.x) as i64, to.y.cmp(&from.y) as i64);
+        let offset = i64::from((stroke_width - 1) / 2);
+
+        // Only the steps that land on screen along the walk's axis are taken, so lines that reach
+        // far off screen take no longer than ones that don't. Thick lines are only widened across
+        // that axis, so no steps beyond the screen's edge are needed.
+        let size = self.size();
+        let extent = i64::from(if steep { size.height } else { size.width });
+        let from_x = i64::from(from.x);
+        let (first, last) = match step.0 {
+            1 => (-from_x, extent - 1 - from_x),
+            -1 => (from_x - (extent - 1), from_x),
+            _ => (0, if (0..extent).contains(&from_x) { 0 } else { -1 }),
+        };
+        let (first, last) = (first.max(0), last.min(dx));
+        if first > last {
+            return;
+        }
+
+        // Work out where the walk is after `first` steps. Each step takes `dy` from the error,
+        // and every time it goes negative the walk moves a row and adds `dx` back, which keeps it
+        // in `0..dx`. The product is taken in `i128`, since both factors can be close to 2^32.
+        let rows = if dx == 0 {
+            0
+        } else {
+            let taken = i128::from(first) * i128::from(dy) - i128::from(dx / 2);
+            (taken + i128::from(dx) - 1).div_euclid(i128::from(dx)) as i64
+        };
+        let mut error = (i128::from(dx / 2) - i128::from(first) * i128::from(dy)
+            + i128::from(rows) * i128::from(dx)) as i64;
+        let mut x = from_x + step.0 * first;
+        let mut y = i64::from(from.y) + step.1 * rows;
+        let mut run_start = x;
+        for i in first..=last {
+            error -= dy;
+            if error < 0 || i == last {
+                // The run on this row ends here, so fill it before moving on to the next row.
+                let top = y - offset;
+                let run = Rectangle::with_corners(
+                    transpose(clamped(run_start, top)),
+                    transpose(clamped(x, top + i64::from(stroke_width) - 1)),
+                );
+                self.fill_solid_storage(&run, color);
+
+                run_start = x + step.0;
+                y += step.1;
+                error += dx;
+            }
+            x += step.0;
+        }
+    }
+
+    /// Flood fills the area around `seed` with a native color using a scanline fill.
+    fn flood_fill_storage(&mut self, seed: Point, color: u32) {
+        let Some(target) = self.stored_pixel(seed) else {
+            return;
+        };
+        if target == color {
+            return;
+        }
+
+        let matches = |driver: &Self, point: Point| driver.stored_pixel(point) == Some(target);
+        let mut seeds = vec![seed];
+        while let Some(seed) = seeds.pop() {
+            if !matches(self, seed) {
+                continue;
+            }
+
+            let mut left = seed.x;
+            while matches(self, Point::new(left - 1, seed.y)) {
+                left -= 1;
+            }
+            let mut right = seed.x;
+            while matches(self, Point::new(right + 1, seed.y)) {
+                right += 1;
+            }
+            let span = Rectangle::with_corners(Point::new(left, seed.y), Point::new(right, seed.y));
+            self.fill_solid_storage(&span, color);
+
+            // Queue the start of every run of matching pixels touching the span from above or
+            // below.
+            for y in [seed.y - 1, seed.y + 1] {
+                let mut in_run = false;
+                for x in left..=right {
+                    let point = Point::new(x, y);
+                    let matched = matches(self, point);
+                    if matched && !in_run {
+                        seeds.push(point);
+                    }
+                    in_run = matched;
+                }
+            }
+        }
+    }
+
+    /// Draws an aspect-corrected circle with a native color, filled if `stroke_width` is `None`,
+    /// and only where it is within `range` if there is one.
+    fn draw_ellipse_storage(
+        &mut self,
+        center: Point,
+        radius: u32,
+        stroke_width: Option<u32>,
+        range: Option<AngleRange>,
+        color: u32,
+    ) {
+        let outer = (self.aspect_width(radius), radius);
+        let inner = stroke_width.and_then(|stroke| {
+            let inner_radius = radius.checked_sub(stroke)?;
+            Some((self.aspect_width(inner_radius), inner_radius))
+        });
+
+        let height = i64::from(self.size().height);
+        let top = (i64::from(center.y) - i64::from(radius)).max(0);
+        let bottom = (i64::from(center.y) + i64::from(radius)).min(height - 1);
+
+        for y in top..=bottom {
+            let y = y as i32;
+            let dy = y.abs_diff(center.y);
+            let Some(outer_half) = ellipse_half_width(outer, dy) else {
+                continue;
+            };
+            let center_x = i64::from(center.x);
+            let left = center_x - i64::from(outer_half);
+            let right = center_x + i64::from(outer_half);
+
+            // Rows above and below the inside of an outline are filled all the way across.
+            let spans = match inner.and_then(|inner| ellipse_half_width(inner, dy)) {
+                Some(inner_half) => [
+                    (left, center_x - i64::from(inner_half) - 1),
+                    (center_x + i64::from(inner_half) + 1, right),
+                ],
+                None => [(left, right), (1, 0)],
+            };
+
+            for (start, end) in spans {
+                match range {
+                    Some(range) => self.fill_arc_row_storage(center, y, start, end, range, color),
+                    None => self.fill_row_storage(y, start, end, color),
+                }
+            }
+        }
+    }
+
+    /// Fills the pixels of columns `start` to `end` of row `y` whose direction from `center` is
+    /// within `range`, with each run of them sent as a single solid fill.
+    fn fill_arc_row_storage(
+        &mut self,
+        center: Point,
+        y: i32,
+        start: i64,
+        end: i64,
+        range: AngleRange,
+        color: u32,
+    ) {
+        let (start, end) = (start.max(0), end.min(i64::from(self.size().width) - 1));
+        let (horizontal, vertical) = self.pixel_aspect;
+        // Columns are scaled back to rows so that angles match the circle as it looks.
+        let scale = vertical as f32 / horizontal as f32;
+        let dy = (i64::from(y) - i64::from(center.y)) as f32;
+        let inside = |x: i64| {
+            let dx = (x - i64::from(center.x)) as f32 * scale;
+            range.contains(dy.atan2(dx))
+        };
+
+        let mut run_start = None;
+        for x in start..=end + 1 {
+            match (run_start, x <= end && inside(x)) {
+                (None, true) => run_start = Some(x),
+                (Some(first), false) => {
+                    self.fill_row_storage(y, first, x - 1, color);
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The directions covered by an arc, in radians clockwise from the positive x axis.
+#[derive(Clone, Copy)]
+struct AngleRange {
+    /// Where the arc starts, from `0` to a full turn.
+    start: f32,
+
+    /// How far the arc goes clockwise from its start, which is never negative.
+    sweep: f32,
+}
+
+impl AngleRange {
+    /// Returns `true` if the direction `angle` is within the arc.
+    fn contains(self, angle: f32) -> bool {
+        self.sweep >= TAU || (angle - self.start).rem_euclid(TAU) <= self.sweep
+    }
+}
+
+/// Returns how far an ellipse with radii `(rx, ry)` extends either side of its center on the row
+/// `dy` rows from its center, or `None` if the row misses the ellipse.
+///
+/// A pixel is inside if its center is inside the ellipse with both radii grown by half a pixel,
+/// which makes a radius of zero a single pixel.
+fn ellipse_half_width((rx, ry): (u32, u32), dy: u32) -> Option<i32> {
+    if dy > ry {
+        return None;
+    }
+
+    let rx = rx as f32 + 0.5;
+    let ry = ry as f32 + 0.5;
+    let t = dy as f32 / ry;
+    Some((rx * (1.0 - t * t).sqrt() - 0.5).round().max(0.0) as i32)
+}
+
+/// Returns the leftmost and rightmost columns of a triangle on row `y`.
+fn triangle_span([p0, p1, p2]: [Point; 3], y: i32) -> Option<(i32, i32)> {
+    let mut span: Option<(i32, i32)> = None;
+
+    for (a, b) in [(p0, p1), (p1, p2), (p2, p0)] {
+        if y < a.y.min(b.y) || y > a.y.max(b.y) {
+            continue;
+        }
+
+        let (start, end) = if a.y == b.y {
+            (a.x.min(b.x), a.x.max(b.x))
+        } else {
+            // The differences are taken in `i64`, since corners far apart can overflow an `i32`.
+            let delta = |from: i32, to: i32| (i64::from(to) - i64::from(from)) as f32;
+            let t = delta(a.y, y) / delta(a.y, b.y);
+            let x = (a.x as f32 + t * delta(a.x, b.x)).round() as i32;
+            (x, x)
+        };
+
+        span = Some(span.map_or((start, end), |(left, right)| {
+            (left.min(start), right.max(end))
+        }));
+    }
+
+    span
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::{
+        prelude::*,
+        primitives::{Line, PrimitiveStyle},
+    };
+    use embedded_graphics_core::pixelcolor::Rgb888;
+
+    use crate::{DisplayDriver, MockPanel, Rotation};
+
+    /// Draws a one pixel wide line both with `draw_line` and as a styled embedded-graphics
+    /// `Line`, and checks that they cover the same pixels.
+    fn assert_matches_line(rotation: Rotation, start: Point, end: Point) {
+        let mut ours = DisplayDriver::new(MockPanel::new());
+        ours.set_rotation(rotation);
+        ours.draw_line(start, end, 1, Rgb888::RED);
+
+        let mut theirs = DisplayDriver::new(MockPanel::new());
+        theirs.set_rotation(rotation);
+        Line::new(start, end)
+            .into_styled(PrimitiveStyle::with_stroke(Rgb888::RED, 1))
+            .draw(&mut theirs)
+            .unwrap();
+
+        assert!(
+            ours.backend().framebuffer() == theirs.backend().framebuffer(),
+            "{start:?} to {end:?} differs under {rotation:?}"
+        );
+    }
+
+    #[test]
+    fn draw_line_matches_embedded_graphics() {
+        // A small linear congruential generator keeps the lines the same from run to run.
+        let mut seed = 1_u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 8) as i32 % 1200 - 360
+        };
+
+        for rotation in [
+            Rotation::Deg0,
+            Rotation::Deg90,
+            Rotation::Deg180,
+            Rotation::Deg270,
+        ] {
+            for _ in 0..50 {
+                let start = Point::new(next(), next());
+                let end = Point::new(next(), next());
+                assert_matches_line(rotation, start, end);
+            }
+        }
+    }
+
+    #[test]
+    fn draw_line_clips_lines_reaching_far_off_screen() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.draw_line(
+            Point::new(i32::MIN, 0),
+            Point::new(i32::MAX, 1),
+            3,
+            Rgb888::RED,
+        );
+        display.draw_line(
+            Point::new(0, i32::MIN),
+            Point::new(1, i32::MAX),
+            3,
+            Rgb888::RED,
+        );
+        display.draw_line(
+            Point::new(i32::MAX, i32::MAX),
+            Point::new(i32::MIN, i32::MIN),
+            u32::MAX,
+            Rgb888::RED,
+        );
+
+        // Skipping to the first visible step lands on the same pixels as walking the whole
+        // line. embedded-graphics' own line drawing overflows for much longer lines than these.
+        assert_matches_line(
+            Rotation::Deg0,
+            Point::new(-5_000, -50),
+            Point::new(5_000, 60),
+        );
+        assert_matches_line(
+            Rotation::Deg90,
+            Point::new(7_000, 200),
+            Point::new(-3_000, 100),
+        );
+    }
+
+    #[test]
+    fn fill_triangle_spans_widen_from_apex_to_base() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        // The corners are out of order on purpose.
+        display.fill_triangle(
+            Point::new(19, 19),
+            Point::new(10, 10),
+            Point::new(10, 19),
+            Rgb888::RED,
+        );
+
+        let panel = display.backend();
+        assert_eq!(panel.fill_rects().len(), 10);
+        for row in 0..10 {
+            let y = 10 + row;
+            let span: Vec<i32> = (0..30)
+                .filter(|&x| panel.pixel(x, y) == Some(0xFF0000))
+                .collect();
+            assert_eq!(span, (10..=10 + row).collect::<Vec<_>>(), "row {y}");
+        }
+        assert!((0..30).all(|x| panel.pixel(x, 9) == Some(0) && panel.pixel(x, 20) == Some(0)));
+    }
+
+    #[test]
+    fn fill_triangle_is_clipped_and_offset() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.set_origin(Point::new(100, 50));
+        display.fill_triangle(
+            Point::new(-10, 0),
+            Point::new(10, 0),
+            Point::new(0, -10),
+            Rgb888::RED,
+        );
+
+        let panel = display.backend();
+        assert_eq!(panel.fill_rects().len(), 1);
+        assert_eq!(panel.pixel(100, 50), Some(0xFF0000));
+        assert_eq!(panel.pixel(110, 50), Some(0xFF0000));
+        assert_eq!(panel.pixel(99, 50), Some(0));
+        assert_eq!(panel.pixel(111, 50), Some(0));
+        assert_eq!(panel.pixel(100, 49), Some(0));
+    }
+}
@@ -0,0 +1,230 @@
+//! Drawing single cells of a sprite sheet.
+
+use embedded_graphics_core::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+
+use crate::{DisplayDriver, PanelBackend, ToDisplayStorage};
+
+/// An image made up of a grid of equally sized cells, like the frames of an animation or the
+/// tiles of a game map.
+///
+/// The sheet borrows its pixels in row-major order, `columns` cells wide. Cells are numbered
+/// from `0` left to right, then top to bottom, and any partial row of cells at the bottom of the
+/// sheet is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics_core::{pixelcolor::Rgb888, prelude::*};
+/// use vexide_embedded_graphics::{DisplayDriver, MockPanel, SpriteSheet};
+///
+/// // Two 2x2 cells side by side: a red one and a blue one.
+/// let pixels = [
+///     Rgb888::RED, Rgb888::RED, Rgb888::BLUE, Rgb888::BLUE,
+///     Rgb888::RED, Rgb888::RED, Rgb888::BLUE, Rgb888::BLUE,
+/// ];
+/// let sheet = SpriteSheet::new(&pixels, Size::new(2, 2), 2);
+///
+/// let mut display = DisplayDriver::new(MockPanel::new());
+/// sheet.draw_cell(&mut display, 1, Point::new(10, 10));
+/// assert_eq!(display.backend().pixel(11, 11), Some(0x0000FF));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpriteSheet<'a, C = Rgb888> {
+    pixels: &'a [C],
+    cell_size: Size,
+    columns: u32,
+}
+
+impl<'a, C> SpriteSheet<'a, C> {
+    /// Creates a sprite sheet of `cell_size` cells, `columns` of them across.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `columns` or either side of `cell_size` is zero.
+    #[must_use]
+    pub const fn new(pixels: &'a [C], cell_size: Size, columns: u32) -> Self {
+        assert!(
+            columns > 0 && cell_size.width > 0 && cell_size.height > 0,
+            "sprite sheet cells and columns must be greater than zero"
+        );
+        Self {
+            pixels,
+            cell_size,
+            columns,
+        }
+    }
+
+    /// Returns the size of each cell.
+    #[must_use]
+    pub const fn cell_size(&self) -> Size {
+        self.cell_size
+    }
+
+    /// Returns the number of cells in each row of the sheet.
+    #[must_use]
+    pub const fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// Returns the number of whole cells in the sheet.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        let row_pixels = self.stride() * self.cell_size.height as usize;
+        self.pixels.len() / row_pixels * self.columns as usize
+    }
+
+    /// Returns `true` if the sheet doesn't hold a single whole cell.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the area of the sheet that cell `index` covers, or `None` if there is no such
+    /// cell.
+    #[must_use]
+    pub const fn cell(&self, index: usize) -> Option<Rectangle> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let columns = self.columns as usize;
+        let top_left = Point::new(
+            ((index % columns) as u32 * self.cell_size.width) as i32,
+            ((index / columns) as u32 * self.cell_size.height) as i32,
+        );
+        Some(Rectangle::new(top_left, self.cell_size))
+    }
+
+    /// Returns the width of the sheet in pixels.
+    const fn stride(&self) -> usize {
+        self.columns as usize * self.cell_size.width as usize
+    }
+}
+
+impl<C: ToDisplayStorage> SpriteSheet<'_, C> {
+    /// Draws cell `index` with its top left corner at `dest`.
+    ///
+    /// Only the cell's own pixels are read, and the visible part of each of its rows is copied to
+    /// the display with a single SDK call, like [`blit_slice`](DisplayDriver::blit_slice). Parts
+    /// of the cell outside the drawable area are clipped, and nothing is drawn if there is no
+    /// cell `index`.
+    pub fn draw_cell<B: PanelBackend>(
+        &self,
+        target: &mut DisplayDriver<C, B>,
+        index: usize,
+        dest: Point,
+    ) {
+        let Some(cell) = self.cell(index) else {
+            return;
+        };
+
+        let stride = self.stride();
+        let start = cell.top_left.y as usize * stride + cell.top_left.x as usize;
+        target.blit_converted(
+            &Rectangle::new(dest, self.cell_size),
+            &self.pixels[start..],
+            stride,
+            |color| color.to_display_storage(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::pixelcolor::raw::RawU24;
+
+    use super::*;
+    use crate::MockPanel;
+
+    const CELL: Size = Size::new(4, 3);
+
+    /// A sheet of two rows of three 4x3 cells, with a different color in every pixel.
+    fn sheet_pixels() -> Vec<Rgb888> {
+        (0..12 * 6)
+            .map(|i| Rgb888::from(RawU24::new(i + 1)))
+            .collect()
+    }
+
+    /// A display with cell `index` drawn at `dest` one pixel at a time.
+    fn drawn_by_pixel(
+        pixels: &[Rgb888],
+        index: usize,
+        dest: Point,
+    ) -> DisplayDriver<Rgb888, MockPanel> {
+        let sheet = SpriteSheet::new(pixels, CELL, 3);
+        let cell = sheet.cell(index).unwrap();
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display
+            .draw_iter(cell.points().map(|point| {
+                let color = pixels[point.y as usize * 12 + point.x as usize];
+                Pixel(dest + (point - cell.top_left), color)
+            }))
+            .unwrap();
+        display
+    }
+
+    fn assert_cell_drawn_at(index: usize, dest: Point) {
+        let pixels = sheet_pixels();
+        let sheet = SpriteSheet::new(&pixels, CELL, 3);
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        sheet.draw_cell(&mut display, index, dest);
+
+        let expected = drawn_by_pixel(&pixels, index, dest);
+        assert!(
+            display.backend().framebuffer() == expected.backend().framebuffer(),
+            "cell {index} at {dest:?} differs"
+        );
+    }
+
+    #[test]
+    fn cells_are_drawn_from_their_own_part_of_the_sheet() {
+        assert_cell_drawn_at(0, Point::new(10, 20));
+        assert_cell_drawn_at(4, Point::new(10, 20));
+    }
+
+    #[test]
+    fn a_cell_at_a_negative_dest_is_clipped() {
+        assert_cell_drawn_at(5, Point::new(-2, -1));
+        assert_cell_drawn_at(5, Point::new(-3, 100));
+    }
+
+    #[test]
+    fn a_cell_hanging_off_the_right_and_bottom_is_clipped() {
+        assert_cell_drawn_at(4, Point::new(478, 238));
+        assert_cell_drawn_at(4, Point::new(100, 239));
+    }
+
+    #[test]
+    fn a_cell_that_is_not_in_the_sheet_draws_nothing() {
+        let pixels = sheet_pixels();
+        let sheet = SpriteSheet::new(&pixels, CELL, 3);
+        let mut display = DisplayDriver::new(MockPanel::new());
+
+        sheet.draw_cell(&mut display, 6, Point::new(10, 10));
+        sheet.draw_cell(&mut display, usize::MAX, Point::new(10, 10));
+
+        assert!(
+            display
+                .backend()
+                .framebuffer()
+                .iter()
+                .all(|&color| color == 0)
+        );
+        assert!(display.backend().fill_rects().is_empty());
+    }
+
+    #[test]
+    fn a_partial_bottom_row_of_cells_is_not_counted() {
+        let mut pixels = sheet_pixels();
+        // Two of the three pixel rows of a third row of cells, and a few stray pixels.
+        pixels.extend(core::iter::repeat_n(Rgb888::RED, 12 * 2 + 5));
+        let sheet = SpriteSheet::new(&pixels, CELL, 3);
+
+        assert_eq!(sheet.len(), 6);
+        assert_eq!(sheet.cell(5), Some(Rectangle::new(Point::new(8, 3), CELL)));
+        assert_eq!(sheet.cell(6), None);
+        assert!(!sheet.is_empty());
+        assert!(SpriteSheet::new(&pixels[..12 * 3 - 1], CELL, 3).is_empty());
+    }
+}
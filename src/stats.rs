@@ -0,0 +1,347 @@
+//! Frame statistics and an on-screen overlay for showing them.
+
+use core::time::Duration;
+use std::time::Instant;
+
+use embedded_graphics::{
+    mono_font::{MonoTextStyle, ascii::FONT_6X10},
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::Rectangle,
+    text::{Baseline, Text},
+};
+
+use crate::{DisplayDriver, OffscreenCanvas, PanelBackend};
+
+/// Timing and draw call counts for the last frame shown by a [`DisplayDriver`].
+///
+/// See [`DisplayDriver::frame_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FrameStats {
+    frame_time: Option<Duration>,
+    draw_calls: u32,
+}
+
+impl FrameStats {
+    /// Returns the time between the last two frames, or `None` before the second frame.
+    #[must_use]
+    pub const fn frame_time(&self) -> Option<Duration> {
+        self.frame_time
+    }
+
+    /// Returns the frame rate that the last [frame time](Self::frame_time) works out to.
+    #[must_use]
+    pub fn fps(&self) -> Option<f32> {
+        self.frame_time
+            .filter(|time| !time.is_zero())
+            .map(|time| 1.0 / time.as_secs_f32())
+    }
+
+    /// Returns the number of drawing calls made to the SDK during the last frame.
+    ///
+    /// Each solid fill, row of an image or single pixel counts as one call, so this shows how well
+    /// drawing code is using the accelerated paths.
+    #[must_use]
+    pub const fn draw_calls(&self) -> u32 {
+        self.draw_calls
+    }
+}
+
+/// Running totals of the drawing calls a [`DisplayDriver`] has made to the SDK, by kind.
+///
+/// Unlike [`FrameStats`], these keep adding up across frames until they are
+/// [reset](DisplayDriver::reset_draw_stats), which makes it easy to profile a single piece of
+/// drawing code. See [`DisplayDriver::draw_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DrawStats {
+    pixel_sets: u64,
+    rect_fills: u64,
+    rect_copies: u64,
+    pixels: u64,
+}
+
+impl DrawStats {
+    /// Returns the number of single pixels set with `vexDisplayPixelSet`.
+    #[must_use]
+    pub const fn pixel_sets(&self) -> u64 {
+        self.pixel_sets
+    }
+
+    /// Returns the number of solid fills made with `vexDisplayRectFill`.
+    #[must_use]
+    pub const fn rect_fills(&self) -> u64 {
+        self.rect_fills
+    }
+
+    /// Returns the number of images, rows and spans copied with `vexDisplayCopyRect`.
+    #[must_use]
+    pub const fn rect_copies(&self) -> u64 {
+        self.rect_copies
+    }
+
+    /// Returns the total number of pixels written by all of the calls above.
+    ///
+    /// Pixels drawn more than once are counted every time, so comparing this to the size of the
+    /// screen shows how much overdraw there is.
+    #[must_use]
+    pub const fn pixels(&self) -> u64 {
+        self.pixels
+    }
+}
+
+/// The corner of the screen the [debug overlay](DisplayDriver::set_debug_overlay) is drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OverlayCorner {
+    /// The top left corner.
+    TopLeft,
+    /// The top right corner.
+    #[default]
+    TopRight,
+    /// The bottom left corner.
+    BottomLeft,
+    /// The bottom right corner.
+    BottomRight,
+}
+
+/// What the driver tracks to produce [`FrameStats`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StatsState {
+    last_frame: FrameStats,
+    draw_calls: u32,
+    totals: DrawStats,
+    last_render: Option<Instant>,
+    overlay: Option<OverlayCorner>,
+}
+
+impl StatsState {
+    /// Counts a drawing call to the SDK.
+    pub(crate) const fn record_draw_call(&mut self) {
+        self.draw_calls = self.draw_calls.saturating_add(1);
+    }
+
+    /// Counts a call to `vexDisplayPixelSet`.
+    pub(crate) const fn record_pixel_set(&mut self) {
+        self.record_draw_call();
+        self.totals.pixel_sets += 1;
+        self.totals.pixels += 1;
+    }
+
+    /// Counts a call to `vexDisplayRectFill` covering `pixels` pixels.
+    pub(crate) const fn record_rect_fill(&mut self, pixels: u32) {
+        self.record_draw_call();
+        self.totals.rect_fills += 1;
+        self.totals.pixels += pixels as u64;
+    }
+
+    /// Counts a call to `vexDisplayCopyRect` covering `pixels` pixels.
+    pub(crate) const fn record_rect_copy(&mut self, pixels: u32) {
+        self.record_draw_call();
+        self.totals.rect_copies += 1;
+        self.totals.pixels += pixels as u64;
+    }
+
+    /// Finishes the current frame's stats and starts counting the next one.
+    pub(crate) fn finish_frame(&mut self) {
+        let now = Instant::now();
+        self.last_frame = FrameStats {
+            frame_time: self.last_render.map(|last| now - last),
+            draw_calls: self.draw_calls,
+        };
+        self.last_render = Some(now);
+        self.draw_calls = 0;
+    }
+}
+
+/// The size of the debug overlay: three lines of text with a pixel of padding around them.
+const OVERLAY_SIZE: Size = Size::new(10 * 6 + 2, 3 * 10 + 2);
+
+impl<C, B> DisplayDriver<C, B> {
+    /// Returns timing and draw call counts for the last frame that was
+    /// [rendered](Self::render).
+    ///
+    /// Only available with the `stats` feature.
+    #[must_use]
+    pub const fn frame_stats(&self) -> FrameStats {
+        self.stats.last_frame
+    }
+
+    /// Returns how many drawing calls of each kind have been made to the SDK since the driver was
+    /// created or the stats were last [reset](Self::reset_draw_stats).
+    ///
+    /// Text drawn with [`draw_builtin_text`](Self::draw_builtin_text) only shows up in the
+    /// [frame stats](Self::frame_stats), since it isn't one of the calls counted here.
+    ///
+    /// Only available with the `stats` feature.
+    #[must_use]
+    pub const fn draw_stats(&self) -> DrawStats {
+        self.stats.totals
+    }
+
+    /// Sets all of the [draw stats](Self::draw_stats) back to zero.
+    ///
+    /// Only available with the `stats` feature.
+    pub fn reset_draw_stats(&mut self) {
+        self.stats.totals = DrawStats::default();
+    }
+
+    /// Shows or hides a readout of the [frame stats](Self::frame_stats) in a corner of the
+    /// screen.
+    ///
+    /// While enabled, the frame rate, frame time and draw call count are drawn over the frame
+    /// every time it is [rendered](Self::render), so they cover whatever is underneath. The
+    /// overlay starts off disabled and in the [top right](OverlayCorner::TopRight) corner.
+    ///
+    /// Only available with the `stats` feature.
+    pub fn set_debug_overlay(&mut self, enabled: bool) {
+        self.stats.overlay = enabled.then(|| self.stats.overlay.unwrap_or_default());
+    }
+
+    /// Moves the [debug overlay](Self::set_debug_overlay) to another corner of the screen and
+    /// enables it.
+    ///
+    /// Only available with the `stats` feature.
+    pub fn set_debug_overlay_corner(&mut self, corner: OverlayCorner) {
+        self.stats.overlay = Some(corner);
+    }
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Draws the debug overlay over the frame, if it's enabled.
+    pub(crate) fn draw_debug_overlay(&mut self) {
+        let Some(corner) = self.stats.overlay else {
+            return;
+        };
+
+        let stats = self.stats.last_frame;
+        let fps = stats
+            .fps()
+            .map_or_else(|| "-- fps".to_owned(), |fps| format!("{fps:.0} fps"));
+        let frame_time = stats.frame_time().map_or_else(
+            || "-- ms".to_owned(),
+            |time| format!("{:.1} ms", time.as_secs_f32() * 1000.0),
+        );
+        let text = format!("{fps}\n{frame_time}\n{} calls", stats.draw_calls());
+
+        let mut canvas = OffscreenCanvas::new(OVERLAY_SIZE);
+        let style = MonoTextStyle::new(&FONT_6X10, Rgb888::WHITE);
+        let Ok(_) =
+            Text::with_baseline(&text, Point::new(1, 1), style, Baseline::Top).draw(&mut canvas);
+
+        let screen = self.screen_bounds();
+        let right = (screen.size.width - OVERLAY_SIZE.width) as i32;
+        let bottom = (screen.size.height.saturating_sub(OVERLAY_SIZE.height)) as i32;
+        let top_left = match corner {
+            OverlayCorner::TopLeft => Point::zero(),
+            OverlayCorner::TopRight => Point::new(right, 0),
+            OverlayCorner::BottomLeft => Point::new(0, bottom),
+            OverlayCorner::BottomRight => Point::new(right, bottom),
+        };
+
+        let area = Rectangle::new(top_left, OVERLAY_SIZE).intersection(&screen);
+        if area.is_zero_sized() {
+            return;
+        }
+        let width = area.size.width as usize;
+        for (y, pixels) in area
+            .rows()
+            .zip(canvas.pixels().chunks_exact(OVERLAY_SIZE.width as usize))
+        {
+            self.buffer[..width].copy_from_slice(&pixels[..width]);
+            self.write_span(&Rectangle::new(
+                Point::new(area.top_left.x, y),
+                Size::new(area.size.width, 1),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockPanel;
+
+    /// Returns the bounding box of every pixel that isn't red.
+    fn overlay_area(panel: &MockPanel) -> Rectangle {
+        let width = MockPanel::WIDTH as i32;
+        let (mut min, mut max) = (Point::new(i32::MAX, i32::MAX), Point::new(-1, -1));
+        for (index, &color) in panel.framebuffer().iter().enumerate() {
+            if color != 0xFF0000 {
+                let point = Point::new(index as i32 % width, index as i32 / width);
+                (min, max) = (min.component_min(point), max.component_max(point));
+            }
+        }
+        Rectangle::with_corners(min, max)
+    }
+
+    fn rendered_with_overlay(corner: Option<OverlayCorner>) -> DisplayDriver<Rgb888, MockPanel> {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.clear(Rgb888::RED).unwrap();
+        match corner {
+            Some(corner) => display.set_debug_overlay_corner(corner),
+            None => display.set_debug_overlay(true),
+        }
+        display.render();
+        display
+    }
+
+    #[test]
+    fn the_overlay_is_off_by_default() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.clear(Rgb888::RED).unwrap();
+
+        display.render();
+
+        assert!(
+            display
+                .backend()
+                .framebuffer()
+                .iter()
+                .all(|&c| c == 0xFF0000)
+        );
+    }
+
+    #[test]
+    fn the_overlay_draws_text_in_the_top_right_corner() {
+        let display = rendered_with_overlay(None);
+
+        let panel = display.backend();
+        assert_eq!(
+            overlay_area(panel),
+            Rectangle::new(Point::new(480 - 62, 0), OVERLAY_SIZE)
+        );
+        assert!(panel.framebuffer().contains(&0xFFFFFF));
+    }
+
+    #[test]
+    fn the_overlay_draws_in_the_configured_corner() {
+        for (corner, top_left) in [
+            (OverlayCorner::TopLeft, Point::zero()),
+            (OverlayCorner::BottomLeft, Point::new(0, 240 - 32)),
+            (OverlayCorner::BottomRight, Point::new(480 - 62, 240 - 32)),
+        ] {
+            let display = rendered_with_overlay(Some(corner));
+
+            let area = overlay_area(display.backend());
+            assert_eq!(area, Rectangle::new(top_left, OVERLAY_SIZE), "{corner:?}");
+        }
+    }
+
+    #[test]
+    fn frame_stats_count_the_draw_calls_of_the_last_frame() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        display.render();
+        display.set_pixel(Point::zero(), Rgb888::RED);
+        display
+            .fill_solid(&Rectangle::new(Point::zero(), Size::new(4, 4)), Rgb888::RED)
+            .unwrap();
+
+        display.render();
+
+        let stats = display.frame_stats();
+        assert_eq!(stats.draw_calls(), 2);
+        assert!(stats.frame_time().is_some());
+        assert_eq!(display.draw_stats().pixel_sets(), 1);
+        assert_eq!(display.draw_stats().rect_fills(), 1);
+        assert_eq!(display.draw_stats().pixels(), 17);
+    }
+}
@@ -0,0 +1,184 @@
+//! A drawing interface that can be used as a trait object.
+
+use core::convert::Infallible;
+
+use embedded_graphics_core::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+
+use crate::{DisplayDriver, OffscreenCanvas, PanelBackend, blit::image_size};
+
+/// Something that can be drawn to, like the display, a [`Viewport`](crate::Viewport) or an
+/// [`OffscreenCanvas`].
+///
+/// [`DrawTarget`] has an associated color and error type, which makes it awkward to accept "any
+/// target" behind a `dyn`. This trait always draws [`Rgb888`] colors and can't fail, so UI code
+/// can take a `&mut dyn DrawSurface` and be handed the display, a viewport, a canvas, or anything
+/// else that implements it. `dyn DrawSurface` is itself a [`DrawTarget`], so embedded-graphics drawables can
+/// be drawn straight onto it.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics_core::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+/// use vexide_embedded_graphics::{DrawSurface, OffscreenCanvas};
+///
+/// fn draw_background(surface: &mut dyn DrawSurface) {
+///     let area = Rectangle::new(Point::zero(), surface.surface_size());
+///     surface.fill_rect(&area, Rgb888::BLUE);
+/// }
+///
+/// let mut canvas = OffscreenCanvas::new(Size::new(32, 32));
+/// draw_background(&mut canvas);
+/// assert_eq!(canvas.pixel(Point::new(8, 8)), Some(Rgb888::BLUE));
+/// ```
+pub trait DrawSurface {
+    /// Returns the size of the area that can be drawn to.
+    fn surface_size(&self) -> Size;
+
+    /// Sets a single pixel. Pixels outside of the surface are ignored.
+    fn set_pixel(&mut self, point: Point, color: Rgb888);
+
+    /// Fills a rectangle with a solid color, clipped to the surface.
+    fn fill_rect(&mut self, area: &Rectangle, color: Rgb888);
+
+    /// Draws an image with its top left corner at `top_left`, clipped to the surface.
+    ///
+    /// `pixels` holds the image in row-major order, `width` pixels per row. A trailing partial
+    /// row is ignored.
+    fn blit(&mut self, top_left: Point, pixels: &[Rgb888], width: u32);
+}
+
+impl<C, B: PanelBackend> DrawSurface for DisplayDriver<C, B> {
+    fn surface_size(&self) -> Size {
+        self.size()
+    }
+
+    fn set_pixel(&mut self, point: Point, color: Rgb888) {
+        if let Some(point) = self.to_screen(point) {
+            self.write_pixel(point, color.into_storage());
+        }
+    }
+
+    fn fill_rect(&mut self, area: &Rectangle, color: Rgb888) {
+        self.fill_solid_storage(area, color.into_storage());
+    }
+
+    fn blit(&mut self, top_left: Point, pixels: &[Rgb888], width: u32) {
+        let area = Rectangle::new(top_left, image_size(pixels, width));
+        self.fill_contiguous_storage(&area, pixels.iter().map(|color| color.into_storage()));
+    }
+}
+
+impl DrawSurface for OffscreenCanvas {
+    fn surface_size(&self) -> Size {
+        self.size()
+    }
+
+    fn set_pixel(&mut self, point: Point, color: Rgb888) {
+        let Ok(()) = self.draw_iter([Pixel(point, color)]);
+    }
+
+    fn fill_rect(&mut self, area: &Rectangle, color: Rgb888) {
+        let Ok(()) = self.fill_solid(area, color);
+    }
+
+    fn blit(&mut self, top_left: Point, pixels: &[Rgb888], width: u32) {
+        let area = Rectangle::new(top_left, image_size(pixels, width));
+        let Ok(()) = self.fill_contiguous(&area, pixels.iter().copied());
+    }
+}
+
+impl Dimensions for dyn DrawSurface + '_ {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), self.surface_size())
+    }
+}
+
+impl DrawTarget for dyn DrawSurface + '_ {
+    type Color = Rgb888;
+
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.set_pixel(point, color);
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.fill_rect(area, color);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockPanel;
+
+    /// Draws a background, a pixel and a small image, the way UI code would.
+    fn draw_widget(surface: &mut dyn DrawSurface) {
+        let area = Rectangle::new(Point::zero(), surface.surface_size());
+        surface.fill_rect(&area, Rgb888::BLUE);
+        surface.set_pixel(Point::new(3, 2), Rgb888::RED);
+        surface.blit(
+            Point::new(6, 4),
+            &[Rgb888::GREEN, Rgb888::WHITE, Rgb888::YELLOW, Rgb888::CYAN],
+            2,
+        );
+    }
+
+    /// Checks that `panel` matches `canvas` pixel for pixel, with the canvas at `offset`.
+    fn assert_matches(panel: &MockPanel, canvas: &OffscreenCanvas, offset: Point) {
+        for point in Rectangle::new(Point::zero(), canvas.size()).points() {
+            let on_panel = point + offset;
+            assert_eq!(
+                panel.pixel(on_panel.x, on_panel.y),
+                canvas.pixel(point).map(Rgb888::into_storage),
+                "pixel {point:?} differs"
+            );
+        }
+    }
+
+    #[test]
+    fn one_function_draws_the_same_to_every_surface() {
+        let mut canvas = OffscreenCanvas::new(Size::new(20, 10));
+        let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+
+        draw_widget(&mut canvas);
+        // The display is larger than the canvas, so draw into the canvas' area of it.
+        let area = Rectangle::new(Point::new(100, 50), canvas.size());
+        draw_widget(&mut display.viewport(area));
+
+        assert_eq!(canvas.pixel(Point::new(3, 2)), Some(Rgb888::RED));
+        assert_eq!(canvas.pixel(Point::new(7, 5)), Some(Rgb888::CYAN));
+        assert_matches(display.backend(), &canvas, area.top_left);
+    }
+
+    #[test]
+    fn the_display_and_a_canvas_of_its_size_match() {
+        let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+        let mut canvas = OffscreenCanvas::new(display.size());
+
+        draw_widget(&mut display);
+        draw_widget(&mut canvas);
+
+        assert_matches(display.backend(), &canvas, Point::zero());
+    }
+
+    #[test]
+    fn a_viewport_clips_what_is_drawn_through_it() {
+        let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+
+        draw_widget(&mut display.viewport(Rectangle::new(Point::new(10, 10), Size::new(7, 5))));
+
+        assert_eq!(display.backend().pixel(16, 14), Some(0x00FF00));
+        assert_eq!(display.backend().pixel(17, 14), Some(0));
+        assert_eq!(display.backend().pixel(16, 15), Some(0));
+    }
+}
@@ -0,0 +1,30 @@
+//! Measuring text before it is drawn.
+
+use embedded_graphics::text::{Text, renderer::TextRenderer};
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+/// Returns the area that `text` covers when it is drawn in `style` at `(0, 0)`.
+///
+/// This is the [`bounding_box`](Dimensions::bounding_box) of a [`Text`] at the origin, without
+/// having to build one, for laying text out before drawing it. Like `Text`, the position is the
+/// start of the first line's baseline as chosen by the style, so the box usually starts above
+/// `(0, 0)`; subtract its [`center`](Rectangle::center) from where text should be centered, or
+/// its bottom right corner from where it should end, to find the position to draw it at.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics::mono_font::{MonoTextStyle, ascii::FONT_6X10};
+/// use embedded_graphics_core::{pixelcolor::Rgb888, prelude::*};
+/// use vexide_embedded_graphics::measure_text;
+///
+/// let style = MonoTextStyle::new(&FONT_6X10, Rgb888::WHITE);
+/// let bounds = measure_text("Hello", &style);
+/// assert_eq!(bounds.size, Size::new(30, 10));
+///
+/// // Where to draw the text so that it is centered on the middle of the screen.
+/// let position = Point::new(240, 120) - bounds.center();
+/// ```
+pub fn measure_text<S: TextRenderer + Clone>(text: &str, style: &S) -> Rectangle {
+    Text::new(text, Point::zero(), style.clone()).bounding_box()
+}
@@ -0,0 +1,132 @@
+//! Touch points in embedded-graphics coordinates.
+
+use crate::{DisplayDriver, PanelBackend, TouchEvent, TouchState};
+use embedded_graphics_core::prelude::*;
+
+/// A point where the screen is being touched.
+///
+/// See [`DisplayDriver::touches`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TouchPoint {
+    /// Where the screen is touched, in embedded-graphics coordinates.
+    pub point: Point,
+
+    /// Whether the touch just started ([`TouchState::Pressed`]) or is being held
+    /// ([`TouchState::Held`]).
+    pub state: TouchState,
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Returns every point where the screen is currently being touched.
+    ///
+    /// The V5 touchscreen only reports a single touch, so this yields at most one point. It is an
+    /// iterator so that code written against it keeps working on a backend that can report more,
+    /// but gestures that need two fingers can't be detected on the V5 itself. Points are
+    /// mapped into embedded-graphics coordinates like [`take_touch`](Self::take_touch)'s, but
+    /// nothing is consumed, so this can be called any number of times per frame.
+    pub fn touches(&self) -> impl Iterator<Item = TouchPoint> + use<C, B> {
+        let touch = self.touch_status();
+        self.touch_point()
+            .map(|point| TouchPoint {
+                point,
+                state: touch.state,
+            })
+            .into_iter()
+    }
+
+    /// Sets a function to call whenever [`poll_touch`](Self::poll_touch) sees the touch state
+    /// change, replacing any function set before.
+    ///
+    /// This keeps input handling out of the render loop: register what a touch should do once,
+    /// and call `poll_touch` once per loop. The function gets the full
+    /// [`touch_status`](Self::touch_status), with its point mapped into embedded-graphics
+    /// coordinates like [`touches`](Self::touches)' points.
+    pub fn on_touch(&mut self, callback: impl FnMut(TouchEvent) + 'static) {
+        self.touch_callback = Some(Box::new(callback));
+    }
+
+    /// Removes the function set by [`on_touch`](Self::on_touch).
+    pub fn clear_on_touch(&mut self) {
+        self.touch_callback = None;
+    }
+
+    /// Checks the touchscreen, and calls the [`on_touch`](Self::on_touch) function if its state
+    /// changed since the last poll.
+    ///
+    /// The function is called once when the screen is pressed, once when the touch becomes
+    /// [held](TouchState::Held), and once when it is released, but not again while the state
+    /// stays the same. The state is only checked when this is called, so a tap that starts and
+    /// ends between two polls is missed; see [`is_new_press`](Self::is_new_press) for a way to
+    /// catch those. The screen counts as released before the first poll.
+    pub fn poll_touch(&mut self) {
+        let mut touch = self.touch_status();
+        if core::mem::replace(&mut self.polled_touch, touch.state) == touch.state {
+            return;
+        }
+
+        touch.point = self.to_logical(touch.point);
+        if let Some(callback) = &mut self.touch_callback {
+            callback(touch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use embedded_graphics_core::pixelcolor::Rgb888;
+
+    use super::*;
+    use crate::{MockPanel, Rotation};
+
+    fn pressed_at(point: Point) -> TouchEvent {
+        TouchEvent {
+            state: TouchState::Pressed,
+            point,
+            press_count: 1,
+            release_count: 0,
+        }
+    }
+
+    #[test]
+    fn on_touch_gets_the_same_point_as_touches() {
+        let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+        display.set_rotation(Rotation::Deg90);
+        display
+            .backend_mut()
+            .set_touch_status(pressed_at(Point::new(10, 20)));
+        let seen = Rc::new(Cell::new(None));
+        let sink = Rc::clone(&seen);
+        display.on_touch(move |touch| sink.set(Some(touch.point)));
+
+        display.poll_touch();
+
+        let expected = display.touches().next().map(|touch| touch.point);
+        assert_eq!(seen.get(), expected);
+        assert_eq!(seen.get(), Some(display.to_logical(Point::new(10, 20))));
+        assert_ne!(seen.get(), Some(Point::new(10, 20)));
+    }
+
+    #[test]
+    fn on_touch_is_only_called_when_the_state_changes() {
+        let mut display = DisplayDriver::<Rgb888, _>::new(MockPanel::new());
+        display
+            .backend_mut()
+            .set_touch_status(pressed_at(Point::new(10, 20)));
+        let calls = Rc::new(Cell::new(0));
+        let sink = Rc::clone(&calls);
+        display.on_touch(move |_| sink.set(sink.get() + 1));
+
+        display.poll_touch();
+        display.poll_touch();
+        assert_eq!(calls.get(), 1);
+
+        display.backend_mut().set_touch_status(TouchEvent {
+            state: TouchState::Released,
+            ..pressed_at(Point::new(10, 20))
+        });
+        display.poll_touch();
+        assert_eq!(calls.get(), 2);
+    }
+}
@@ -0,0 +1,258 @@
+//! Drawing into a part of the display.
+
+use embedded_graphics_core::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+
+use crate::{
+    DisplayDriver, DisplayError, DrawSurface, PanelBackend, ToDisplayStorage, blit::image_size,
+    frame::safe_intersection,
+};
+
+/// A draw target for a rectangular part of the display.
+///
+/// `(0, 0)` is the top left corner of the viewport, and anything drawn outside of it is clipped
+/// away, so a widget drawn into a viewport can't scribble over the rest of the screen. Viewports
+/// can be nested with [`viewport`](Self::viewport), and each one clips to the parts of its area
+/// that are inside every viewport it is nested in.
+///
+/// Created with [`DisplayDriver::viewport`].
+pub struct Viewport<'a, C, B: PanelBackend> {
+    driver: &'a mut DisplayDriver<C, B>,
+    offset: Point,
+    size: Size,
+    clip: Rectangle,
+}
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// Returns a draw target for the part of the display covered by `area`.
+    ///
+    /// See [`Viewport`].
+    pub fn viewport(&mut self, area: Rectangle) -> Viewport<'_, C, B> {
+        let clip = safe_intersection(&area, &self.bounding_box());
+        Viewport {
+            driver: self,
+            offset: area.top_left,
+            size: area.size,
+            clip,
+        }
+    }
+}
+
+impl<C, B: PanelBackend> Viewport<'_, C, B> {
+    /// Returns a draw target for the part of this viewport covered by `area`, which is relative
+    /// to this viewport's top left corner.
+    pub fn viewport(&mut self, area: Rectangle) -> Viewport<'_, C, B> {
+        let area = Rectangle::new(shift(area.top_left, self.offset), area.size);
+        Viewport {
+            driver: self.driver,
+            offset: area.top_left,
+            size: area.size,
+            clip: safe_intersection(&area, &self.clip),
+        }
+    }
+
+    /// Returns the viewport's area in the display's coordinates.
+    #[must_use]
+    pub const fn area(&self) -> Rectangle {
+        Rectangle::new(self.offset, self.size)
+    }
+
+    /// Maps a rectangle to the display's coordinates, clipped to the viewport.
+    fn display_rect(&self, area: &Rectangle) -> Rectangle {
+        safe_intersection(
+            &Rectangle::new(shift(area.top_left, self.offset), area.size),
+            &self.clip,
+        )
+    }
+}
+
+/// Moves `point` by `offset`, saturating instead of overflowing.
+///
+/// A point that saturates is far outside the display anyway, so it gets clipped away either way.
+const fn shift(point: Point, offset: Point) -> Point {
+    Point::new(
+        point.x.saturating_add(offset.x),
+        point.y.saturating_add(offset.y),
+    )
+}
+
+impl<C, B: PanelBackend> OriginDimensions for Viewport<'_, C, B> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<C: ToDisplayStorage, B: PanelBackend> DrawTarget for Viewport<'_, C, B> {
+    type Color = C;
+
+    type Error = DisplayError;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            let point = shift(point, self.offset);
+            if self.clip.contains(point)
+                && let Some(point) = self.driver.to_screen(point)
+            {
+                self.driver.write_pixel(point, color.to_display_storage());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let area = Rectangle::new(shift(area.top_left, self.offset), area.size);
+        let colors = colors.into_iter().map(C::to_display_storage);
+        if self
+            .driver
+            .fill_contiguous_within(&area, &self.clip, colors)
+        {
+            Ok(())
+        } else {
+            Err(DisplayError::ColorBufferTooSmall)
+        }
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = self.display_rect(area);
+        self.driver
+            .fill_solid_storage(&area, color.to_display_storage());
+
+        Ok(())
+    }
+}
+
+impl<C, B: PanelBackend> DrawSurface for Viewport<'_, C, B> {
+    fn surface_size(&self) -> Size {
+        self.size
+    }
+
+    fn set_pixel(&mut self, point: Point, color: Rgb888) {
+        let point = shift(point, self.offset);
+        if self.clip.contains(point)
+            && let Some(point) = self.driver.to_screen(point)
+        {
+            self.driver.write_pixel(point, color.into_storage());
+        }
+    }
+
+    fn fill_rect(&mut self, area: &Rectangle, color: Rgb888) {
+        let area = self.display_rect(area);
+        self.driver.fill_solid_storage(&area, color.into_storage());
+    }
+
+    fn blit(&mut self, top_left: Point, pixels: &[Rgb888], width: u32) {
+        let area = Rectangle::new(shift(top_left, self.offset), image_size(pixels, width));
+        self.driver.fill_contiguous_within(
+            &area,
+            &self.clip,
+            pixels.iter().map(|color| color.into_storage()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::pixelcolor::Rgb888;
+
+    use super::*;
+    use crate::MockPanel;
+
+    #[test]
+    fn nested_viewports_add_their_offsets_and_clip_to_the_inner_one() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let mut outer = display.viewport(Rectangle::new(Point::new(100, 50), Size::new(60, 40)));
+        let mut inner = outer.viewport(Rectangle::new(Point::new(10, 5), Size::new(4, 3)));
+
+        assert_eq!(
+            inner.area(),
+            Rectangle::new(Point::new(110, 55), Size::new(4, 3))
+        );
+        inner
+            .fill_solid(
+                &Rectangle::new(Point::new(-2, -2), Size::new(20, 20)),
+                Rgb888::RED,
+            )
+            .unwrap();
+        inner
+            .draw_iter([
+                Pixel(Point::new(1, 1), Rgb888::GREEN),
+                Pixel(Point::new(4, 1), Rgb888::GREEN),
+            ])
+            .unwrap();
+
+        let mut expected = DisplayDriver::new(MockPanel::new());
+        expected
+            .fill_solid(
+                &Rectangle::new(Point::new(110, 55), Size::new(4, 3)),
+                Rgb888::RED,
+            )
+            .unwrap();
+        expected.set_pixel(Point::new(111, 56), Rgb888::GREEN);
+        assert!(
+            display.backend().framebuffer() == expected.backend().framebuffer(),
+            "the inner viewport should only draw inside its own area"
+        );
+    }
+
+    #[test]
+    fn an_inner_viewport_is_clipped_to_the_outer_one() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let mut outer = display.viewport(Rectangle::new(Point::new(100, 50), Size::new(10, 10)));
+        let mut inner = outer.viewport(Rectangle::new(Point::new(8, 8), Size::new(10, 10)));
+
+        let area = Rectangle::new(Point::zero(), inner.size());
+        inner.fill_solid(&area, Rgb888::RED).unwrap();
+
+        assert_eq!(display.backend().pixel(108, 58), Some(0xFF0000));
+        assert_eq!(display.backend().pixel(109, 59), Some(0xFF0000));
+        assert_eq!(display.backend().pixel(110, 59), Some(0));
+        assert_eq!(display.backend().pixel(109, 60), Some(0));
+    }
+
+    #[test]
+    fn extreme_offsets_saturate_instead_of_wrapping() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let far = Point::new(i32::MAX - 5, i32::MAX - 5);
+        let mut outer = display.viewport(Rectangle::new(far, Size::new(10, 10)));
+        let inner = outer.viewport(Rectangle::new(far, Size::new(10, 10)));
+
+        assert_eq!(inner.area().top_left, Point::new(i32::MAX, i32::MAX));
+        assert_eq!(
+            shift(Point::new(i32::MIN, 5), Point::new(-1, i32::MAX)),
+            Point::new(i32::MIN, i32::MAX)
+        );
+    }
+
+    #[test]
+    fn drawing_far_outside_a_viewport_does_not_overflow() {
+        let mut display = DisplayDriver::new(MockPanel::new());
+        let mut viewport = display.viewport(Rectangle::new(Point::new(100, 50), Size::new(10, 10)));
+
+        // Adding the offset to these would overflow, which panics in debug builds.
+        let far = Point::new(i32::MAX, i32::MAX);
+        viewport
+            .fill_solid(&Rectangle::new(far, Size::new(20, 20)), Rgb888::RED)
+            .unwrap();
+        viewport.draw_iter([Pixel(far, Rgb888::RED)]).unwrap();
+        viewport
+            .fill_contiguous(
+                &Rectangle::new(far, Size::new(2, 2)),
+                core::iter::repeat_n(Rgb888::RED, 4),
+            )
+            .unwrap();
+
+        assert!(
+            display
+                .backend()
+                .framebuffer()
+                .iter()
+                .all(|&color| color == 0)
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Rendering in chunks that let other tasks run in between.
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use std::time::Instant;
+
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+
+use crate::{DisplayDriver, PanelBackend};
+
+/// Number of rows copied to the panel between yields in [`DisplayDriver::render_async`].
+const BAND_HEIGHT: u32 = 16;
+
+impl<C, B: PanelBackend> DisplayDriver<C, B> {
+    /// [Renders](Self::render) the display, yielding to other tasks while the driver copies its
+    /// own buffers to the panel.
+    ///
+    /// A render can involve a lot of copying before the SDK presents the frame: draws waiting in
+    /// the [managed region](Self::set_managed_region) and any [layers](Self::add_layer) are
+    /// copied from memory, which can take a few milliseconds for a full screen. This does that
+    /// copying in bands of 16 rows and yields after each one, so other tasks, like ones reading
+    /// sensors, aren't held up for the whole render. The SDK's own flush of the double buffer
+    /// still happens all at once at the end.
+    ///
+    /// The tradeoff is latency: other tasks can run for as long as they like between bands, so
+    /// the frame may show up later than it would with [`render`](Self::render), and the total
+    /// time spent rendering goes up slightly. Use `render` when the frame should be presented as
+    /// soon as possible, or when there is nothing else to run.
+    pub async fn render_async(&mut self) {
+        #[cfg(feature = "stats")]
+        self.draw_debug_overlay();
+
+        if let Some(pending) = self.take_managed_pending() {
+            for band in bands(&pending) {
+                self.copy_shadow_to_panel(&band);
+                yield_now().await;
+            }
+        }
+        if let Some(area) = self.take_composite_area() {
+            for band in bands(&area) {
+                self.composite_area(&band);
+                yield_now().await;
+            }
+        }
+
+        #[cfg(feature = "stats")]
+        self.stats.finish_frame();
+        self.display.render();
+        self.last_render = Some(Instant::now());
+        self.dirty.clear();
+    }
+}
+
+/// Splits a non-empty area into bands of up to [`BAND_HEIGHT`] rows.
+fn bands(area: &Rectangle) -> impl Iterator<Item = Rectangle> + use<> {
+    let area = *area;
+    (0..area.size.height)
+        .step_by(BAND_HEIGHT as usize)
+        .map(move |row| {
+            Rectangle::new(
+                area.top_left + Point::new(0, row as i32),
+                Size::new(area.size.width, BAND_HEIGHT.min(area.size.height - row)),
+            )
+        })
+}
+
+/// Returns a future that is pending once before it completes, giving the executor a chance to
+/// run other tasks.
+fn yield_now() -> impl Future<Output = ()> {
+    struct YieldNow {
+        yielded: bool,
+    }
+
+    impl Future for YieldNow {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.yielded {
+                return Poll::Ready(());
+            }
+
+            self.yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    YieldNow { yielded: false }
+}